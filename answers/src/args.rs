@@ -0,0 +1,139 @@
+//! The command line every day's binary takes, so it can be run on anyone's puzzle input without
+//! recompiling:
+//!
+//! ```text
+//! dayN [INPUT] [--output PATH]
+//! ```
+//!
+//! INPUT is a file to read the puzzle input from, or `-` to read it from stdin. Without it, the
+//! input bundled with the day is used. `--output` is only taken by days that save a picture.
+
+use std::io::Read;
+use std::process::exit;
+
+#[derive(Debug, Default, PartialEq)]
+pub struct DayArgs {
+    pub input_path: Option<String>,
+    pub output_path: Option<String>,
+}
+
+impl DayArgs {
+    pub fn parse(args: &[String], takes_output: bool) -> Result<DayArgs, String> {
+        let mut day_args = DayArgs::default();
+
+        let mut args = args.iter();
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--output" if takes_output => {
+                    let path = args
+                        .next()
+                        .ok_or_else(|| format!("Missing value for {}", arg))?;
+                    day_args.output_path = Some(path.clone());
+                }
+                "-" if day_args.input_path.is_none() => day_args.input_path = Some(arg.clone()),
+                _ if arg.starts_with('-') => return Err(format!("Unknown option {}", arg)),
+                _ if day_args.input_path.is_none() => day_args.input_path = Some(arg.clone()),
+                _ => return Err(format!("Unexpected argument '{}'", arg)),
+            }
+        }
+        Ok(day_args)
+    }
+
+    /// Parses the process's arguments, exiting with the usage if they're bad.
+    pub fn from_env(takes_output: bool) -> DayArgs {
+        let args: Vec<String> = std::env::args().skip(1).collect();
+        DayArgs::parse(&args, takes_output).unwrap_or_else(|e| {
+            let output = if takes_output { " [--output PATH]" } else { "" };
+            eprintln!("{}\n\nUsage: [INPUT | -]{}", e, output);
+            exit(1);
+        })
+    }
+
+    /// The puzzle input: the file given, stdin, or else `bundled`. Exits if it can't be read.
+    pub fn input(&self, bundled: &str) -> String {
+        match &self.input_path {
+            Some(path) => read_input(path, &mut std::io::stdin()).unwrap_or_else(|e| {
+                eprintln!("{}", e);
+                exit(1);
+            }),
+            None => bundled.to_string(),
+        }
+    }
+
+    /// Where to save the day's output, or `default` if no path was given.
+    pub fn output_path<'a>(&'a self, default: &'a str) -> &'a str {
+        self.output_path.as_deref().unwrap_or(default)
+    }
+}
+
+/// The value, or else exits with the error, e.g. when the puzzle input can't be solved.
+pub fn or_exit<T>(result: Result<T, String>) -> T {
+    result.unwrap_or_else(|e| {
+        eprintln!("{}", e);
+        exit(1);
+    })
+}
+
+/// Reads puzzle input from the file at `path`, or from `stdin` if the path is `-`.
+pub fn read_input(path: &str, stdin: &mut dyn Read) -> Result<String, String> {
+    if path == "-" {
+        let mut input = String::new();
+        stdin
+            .read_to_string(&mut input)
+            .map_err(|e| format!("Can't read stdin: {}", e))?;
+        Ok(input)
+    } else {
+        std::fs::read_to_string(path).map_err(|e| format!("Can't read '{}': {}", path, e))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::args::{read_input, DayArgs};
+
+    fn args(s: &str) -> Vec<String> {
+        s.split_whitespace().map(|it| it.to_string()).collect()
+    }
+
+    #[test]
+    fn test_parse_args() {
+        assert_eq!(
+            DayArgs::parse(&args(""), false).unwrap(),
+            DayArgs::default()
+        );
+
+        let day_args = DayArgs::parse(&args("in.txt --output out.png"), true).unwrap();
+        assert_eq!(day_args.input_path, Some("in.txt".to_string()));
+        assert_eq!(day_args.output_path("default.png"), "out.png");
+
+        let day_args = DayArgs::parse(&args("-"), true).unwrap();
+        assert_eq!(day_args.input_path, Some("-".to_string()));
+        assert_eq!(day_args.output_path("default.png"), "default.png");
+
+        assert!(DayArgs::parse(&args("--output out.png"), false).is_err());
+        assert!(DayArgs::parse(&args("--output"), true).is_err());
+        assert!(DayArgs::parse(&args("a.txt b.txt"), false).is_err());
+        assert!(DayArgs::parse(&args("--fast"), false).is_err());
+    }
+
+    #[test]
+    fn test_read_input() {
+        assert_eq!(
+            read_input("-", &mut "12\n14\n".as_bytes()).unwrap(),
+            "12\n14\n"
+        );
+
+        let path = std::env::temp_dir().join("answers-test-input.txt");
+        std::fs::write(&path, "1969\n").unwrap();
+        let path = path.to_str().unwrap();
+        assert_eq!(
+            read_input(path, &mut "unused".as_bytes()).unwrap(),
+            "1969\n"
+        );
+        std::fs::remove_file(path).unwrap();
+
+        assert!(read_input("/nonexistent/input.txt", &mut "".as_bytes())
+            .unwrap_err()
+            .starts_with("Can't read '/nonexistent/input.txt'"));
+    }
+}
@@ -0,0 +1,119 @@
+//! The answers to a day's puzzle, as every day crate's `solve` returns them.
+
+use std::fmt::{Display, Formatter};
+use std::time::{Duration, Instant};
+
+pub mod args;
+
+/// The answer to one part of a puzzle, and how long it took to find.
+#[derive(Debug, Clone)]
+pub struct Answer {
+    /// The answer, or why it couldn't be found, e.g. for an input the solution doesn't handle.
+    pub value: Result<String, String>,
+    pub time: Duration,
+}
+
+impl Answer {
+    /// Finds an answer with `solve`, timing how long it takes.
+    pub fn timed<T: Display, F: FnOnce() -> Result<T, String>>(solve: F) -> Answer {
+        let start = Instant::now();
+        let value = solve().map(|value| value.to_string());
+        Answer {
+            value,
+            time: start.elapsed(),
+        }
+    }
+}
+
+impl Display for Answer {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), std::fmt::Error> {
+        match &self.value {
+            Ok(value) => write!(f, "{}", value),
+            Err(e) => write!(f, "failed: {}", e),
+        }
+    }
+}
+
+/// Finds the answer to one part, or fails.
+type Part<'a> = Box<dyn FnOnce() -> Answer + 'a>;
+
+/// The answers to a day's puzzle. Each part is only solved when it's asked for, so asking for one
+/// part doesn't take the time to solve the other.
+pub struct Answers<'a> {
+    part1: Part<'a>,
+    /// Missing for day 25, which doesn't have a second puzzle, and for days whose second part
+    /// hasn't been solved.
+    part2: Option<Part<'a>>,
+}
+
+impl<'a> Answers<'a> {
+    pub fn new<A, B, F, G>(part1: F, part2: G) -> Answers<'a>
+    where
+        A: Display,
+        B: Display,
+        F: FnOnce() -> Result<A, String> + 'a,
+        G: FnOnce() -> Result<B, String> + 'a,
+    {
+        Answers {
+            part1: Box::new(|| Answer::timed(part1)),
+            part2: Some(Box::new(|| Answer::timed(part2))),
+        }
+    }
+
+    pub fn part1_only<A, F>(part1: F) -> Answers<'a>
+    where
+        A: Display,
+        F: FnOnce() -> Result<A, String> + 'a,
+    {
+        Answers {
+            part1: Box::new(|| Answer::timed(part1)),
+            part2: None,
+        }
+    }
+
+    /// Solves part 1 or 2, without solving the other.
+    pub fn part(self, part: u8) -> Option<Answer> {
+        match part {
+            1 => Some((self.part1)()),
+            2 => self.part2.map(|part2| part2()),
+            _ => None,
+        }
+    }
+
+    /// Solves both parts, one after the other.
+    pub fn solve_all(self) -> (Answer, Option<Answer>) {
+        let part1 = (self.part1)();
+        (part1, self.part2.map(|part2| part2()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Answers;
+
+    #[test]
+    fn test_answers() {
+        let (part1, part2) = Answers::new(|| Ok(42), || Ok("forty-two")).solve_all();
+        assert_eq!(part1.to_string(), "42");
+        assert_eq!(part2.unwrap().value, Ok("forty-two".to_string()));
+        assert!(Answers::new(|| Ok(1), || Ok(2)).part(3).is_none());
+
+        let answers = Answers::part1_only(|| Ok(1));
+        assert!(answers.part(2).is_none());
+    }
+
+    #[test]
+    fn test_parts_are_solved_separately() {
+        let answers = Answers::new(|| -> Result<i32, _> { panic!("solved part 1") }, || Ok(2));
+        assert_eq!(answers.part(2).unwrap().value, Ok("2".to_string()));
+    }
+
+    #[test]
+    fn test_failed_part() {
+        let answers = Answers::new(|| Err::<i32, _>("no answer".to_string()), || Ok(2));
+        let (part1, part2) = answers.solve_all();
+        assert_eq!(part1.value, Err("no answer".to_string()));
+        assert_eq!(part1.to_string(), "failed: no answer");
+        assert_eq!(part2.unwrap().to_string(), "2");
+    }
+}
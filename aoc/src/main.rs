@@ -0,0 +1,757 @@
+use crate::registry::{input_hash, Entry, Registry};
+use answers::args::read_input;
+use answers::{Answer, Answers};
+use std::any::Any;
+use std::io::Write;
+use std::panic::{catch_unwind, AssertUnwindSafe};
+use std::process::exit;
+use std::time::Duration;
+
+mod registry;
+
+const USAGE: &str = "Usage: aoc run <day> [options]
+       aoc all [--json]
+       aoc verify [--registry PATH] [INPUT...]
+       aoc register <day> [--input PATH]
+
+Commands:
+  run <day>       solve one day's puzzle
+  all             solve every day's puzzle, with the time each part took
+  verify          solve every day's puzzle, and any other inputs given, and check the answers and
+                  times against the registry. Times are registered from a release build, so this
+                  should be run with --release too
+  register <day>  print the registry lines for a day's answers, to add to the registry
+
+Options:
+  --part 1|2        only show the answer to one part
+  --input PATH      solve the puzzle for the input in PATH instead of the bundled one, or for the
+                    input on stdin if PATH is -
+  --json            print the answers as JSON
+  --registry PATH   check against the registry in PATH instead of aoc/registry.txt";
+
+const EXIT_ERROR: i32 = 1;
+
+/// The expected answers for every day's bundled input.
+const REGISTRY: &str = include_str!("../registry.txt");
+
+/// An answer is slower than registered if it takes this many times as long, and longer by at
+/// least `SLOWDOWN_MIN`, so that the fastest days aren't reported for noise.
+const SLOWDOWN_FACTOR: u32 = 2;
+const SLOWDOWN_MIN: Duration = Duration::from_millis(10);
+
+type Solver = fn(&str) -> Result<Answers<'_>, String>;
+
+/// Every day's solver and the input bundled with it.
+const DAYS: &[(u8, Solver, &str)] = &[
+    (1, day1::solve, day1::INPUT),
+    (2, day2::solve, day2::INPUT),
+    (3, day3::solve, day3::INPUT),
+    (4, day4::solve, day4::INPUT),
+    (5, day5::solve, day5::INPUT),
+    (6, day6::solve, day6::INPUT),
+    (7, day7::solve, day7::INPUT),
+    (8, day8::solve, day8::INPUT),
+    (9, day9::solve, day9::INPUT),
+    (10, day10::solve, day10::INPUT),
+    (11, day11::solve, day11::INPUT),
+    (12, day12::solve, day12::INPUT),
+    (13, day13::solve, day13::INPUT),
+    (14, day14::solve, day14::INPUT),
+    (15, day15::solve, day15::INPUT),
+    (16, day16::solve, day16::INPUT),
+    (17, day17::solve, day17::INPUT),
+    (18, day18::solve, day18::INPUT),
+    (19, day19::solve, day19::INPUT),
+    (21, day21::solve, day21::INPUT),
+    (22, day22::solve, day22::INPUT),
+    (23, day23::solve, day23::INPUT),
+    (24, day24::solve, day24::INPUT),
+    (25, day25::solve, day25::INPUT),
+];
+
+fn main() {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let options = match Options::parse(&args) {
+        Ok(options) => options,
+        Err(e) => {
+            eprintln!("{}\n\n{}", e, USAGE);
+            exit(EXIT_ERROR);
+        }
+    };
+
+    let stdout = std::io::stdout();
+    if let Err(e) = run(&options, &mut stdout.lock()) {
+        eprintln!("{}", e);
+        exit(EXIT_ERROR);
+    }
+}
+
+#[derive(Debug, PartialEq)]
+enum Command {
+    Run {
+        day: u8,
+        part: Option<u8>,
+        input_path: Option<String>,
+    },
+    All,
+    Verify {
+        registry_path: Option<String>,
+        input_paths: Vec<String>,
+    },
+    Register {
+        day: u8,
+        input_path: Option<String>,
+    },
+}
+
+#[derive(Debug, PartialEq)]
+struct Options {
+    command: Command,
+    json: bool,
+}
+
+impl Options {
+    fn parse(args: &[String]) -> Result<Options, String> {
+        let mut command = None;
+        let mut day = None;
+        let mut part = None;
+        let mut input_path = None;
+        let mut json = false;
+        let mut registry_path = None;
+        let mut input_paths = Vec::new();
+
+        let mut args = args.iter();
+        while let Some(arg) = args.next() {
+            let mut value = || {
+                args.next()
+                    .ok_or_else(|| format!("Missing value for {}", arg))
+            };
+
+            match arg.as_str() {
+                "--part" => {
+                    part = match value()?.as_str() {
+                        "1" => Some(1),
+                        "2" => Some(2),
+                        part => return Err(format!("Unknown part '{}'", part)),
+                    }
+                }
+                "--input" => input_path = Some(value()?.clone()),
+                "--json" => json = true,
+                "--registry" => registry_path = Some(value()?.clone()),
+                _ if arg.starts_with("--") => return Err(format!("Unknown option {}", arg)),
+                _ if command.is_none() => command = Some(arg.clone()),
+                _ if command.as_deref() == Some("verify") => input_paths.push(arg.clone()),
+                _ if matches!(command.as_deref(), Some("run") | Some("register"))
+                    && day.is_none() =>
+                {
+                    day = Some(parse_day(arg)?)
+                }
+                _ => return Err(format!("Unexpected argument '{}'", arg)),
+            }
+        }
+
+        if registry_path.is_some() && command.as_deref() != Some("verify") {
+            return Err("--registry only applies to verify".to_string());
+        }
+        let command = match command.as_deref() {
+            Some("run") => Command::Run {
+                day: day.ok_or("No day given")?,
+                part,
+                input_path,
+            },
+            Some("all") if part.is_some() || input_path.is_some() => {
+                return Err("--part and --input only apply to a single day".to_string())
+            }
+            Some("all") => Command::All,
+            Some("verify") if part.is_some() || input_path.is_some() || json => {
+                return Err("verify only takes --registry and input files".to_string())
+            }
+            Some("verify") => Command::Verify {
+                registry_path,
+                input_paths,
+            },
+            Some("register") if part.is_some() || json => {
+                return Err("register only takes --input".to_string())
+            }
+            Some("register") => Command::Register {
+                day: day.ok_or("No day given")?,
+                input_path,
+            },
+            Some(command) => return Err(format!("Unknown command '{}'", command)),
+            None => return Err("No command given".to_string()),
+        };
+        Ok(Options { command, json })
+    }
+}
+
+fn parse_day(s: &str) -> Result<u8, String> {
+    s.parse::<u8>()
+        .ok()
+        .filter(|day| DAYS.iter().any(|(d, _, _)| d == day))
+        .ok_or_else(|| format!("There's no solution for day '{}'", s))
+}
+
+fn run(options: &Options, out: &mut dyn Write) -> Result<(), String> {
+    let write_error = |e: std::io::Error| format!("Can't write output: {}", e);
+    match &options.command {
+        Command::Run {
+            day,
+            part,
+            input_path,
+        } => {
+            let (solve, input) = day_input(*day, input_path)?;
+            let answers = solve(&input)?;
+            let parts = match part {
+                Some(part) => vec![(
+                    *part,
+                    answers
+                        .part(*part)
+                        .ok_or_else(|| format!("Day {} has no part {}", day, part))?,
+                )],
+                None => all_parts(answers),
+            };
+
+            if options.json {
+                writeln!(out, "{}", day_json(*day, &parts)).map_err(write_error)?;
+            } else {
+                for (part, answer) in parts.iter() {
+                    write_answer(out, *day, *part, answer).map_err(write_error)?;
+                }
+            }
+            check_parts(&parts)?;
+        }
+        Command::All => {
+            let mut days_json = Vec::new();
+            let mut total = Duration::default();
+            let mut all_parts_solved = Vec::new();
+            for (day, solve, input) in DAYS.iter() {
+                let parts = all_parts(solve(input)?);
+                total += parts
+                    .iter()
+                    .map(|(_, answer)| answer.time)
+                    .sum::<Duration>();
+
+                if options.json {
+                    days_json.push(day_json(*day, &parts));
+                } else {
+                    for (part, answer) in parts.iter() {
+                        write_answer(out, *day, *part, answer).map_err(write_error)?;
+                    }
+                }
+                all_parts_solved.extend(parts);
+            }
+
+            if options.json {
+                writeln!(out, "[{}]", days_json.join(",")).map_err(write_error)?;
+            } else {
+                writeln!(out, "Total: {:.2?}", total).map_err(write_error)?;
+            }
+            check_parts(&all_parts_solved)?;
+        }
+        Command::Verify {
+            registry_path,
+            input_paths,
+        } => {
+            let registry = match registry_path {
+                Some(path) => read_input(path, &mut std::io::stdin())?,
+                None => REGISTRY.to_string(),
+            };
+            let registry = Registry::parse(&registry)
+                .map_err(|e| format!("Can't parse the registry: {}", e))?;
+            let mut inputs = Vec::new();
+            for path in input_paths {
+                inputs.push((path.clone(), read_input(path, &mut std::io::stdin())?));
+            }
+            verify(&registry, DAYS, &inputs, out)?;
+        }
+        Command::Register { day, input_path } => {
+            let (solve, input) = day_input(*day, input_path)?;
+            for (part, answer) in all_parts(solve(&input)?) {
+                let entry = Entry {
+                    day: *day,
+                    part,
+                    input_hash: input_hash(&input),
+                    time: Some(answer.time),
+                    answer: answer
+                        .value
+                        .map_err(|e| format!("Day {}-{} failed: {}", day, part, e))?,
+                };
+                writeln!(out, "{}", entry).map_err(write_error)?;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// A day's solver and the input at `input_path`, or the bundled input.
+fn day_input(day: u8, input_path: &Option<String>) -> Result<(Solver, String), String> {
+    let (_, solve, bundled_input) = DAYS.iter().find(|(d, _, _)| *d == day).unwrap();
+    let input = match input_path {
+        Some(path) => read_input(path, &mut std::io::stdin())?,
+        None => bundled_input.to_string(),
+    };
+    Ok((*solve, input))
+}
+
+/// Solves every day's bundled input, and each of `inputs` for the days it's registered for, and
+/// reports the answers that don't match the registry or that are slower than registered. A day
+/// that fails or panics counts as a wrong answer, and the other days are still checked. Fails if
+/// any answer doesn't match.
+fn verify(
+    registry: &Registry,
+    days: &[(u8, Solver, &str)],
+    inputs: &[(String, String)],
+    out: &mut dyn Write,
+) -> Result<(), String> {
+    let write_error = |e: std::io::Error| format!("Can't write output: {}", e);
+
+    // (day, solver, input, where it's from)
+    let mut jobs: Vec<(u8, Solver, &str, Option<&str>)> = days
+        .iter()
+        .map(|(day, solve, input)| (*day, *solve, *input, None))
+        .collect();
+    let (mut checked, mut wrong, mut slower) = (0, 0, 0);
+    for (path, input) in inputs {
+        let input_days = registry.days_for(input_hash(input));
+        if input_days.is_empty() {
+            writeln!(out, "{}: no answers registered for this input", path).map_err(write_error)?;
+            wrong += 1;
+        }
+        for day in input_days {
+            if let Some((_, solve, _)) = days.iter().find(|(d, _, _)| *d == day) {
+                jobs.push((day, *solve, input, Some(path)));
+            }
+        }
+    }
+
+    for (day, solve, input, path) in jobs {
+        let hash = input_hash(input);
+        let label = |day_part: String| match path {
+            Some(path) => format!("Day {} ({})", day_part, path),
+            None => format!("Day {}", day_part),
+        };
+        let parts = match catch_unwind(AssertUnwindSafe(|| solve(input).map(all_parts))) {
+            Ok(Ok(parts)) => parts,
+            Ok(Err(e)) => {
+                wrong += 1;
+                writeln!(out, "{}: failed: {}", label(day.to_string()), e).map_err(write_error)?;
+                continue;
+            }
+            Err(panic) => {
+                wrong += 1;
+                let message = panic_message(&*panic);
+                writeln!(out, "{}: panicked: {}", label(day.to_string()), message)
+                    .map_err(write_error)?;
+                continue;
+            }
+        };
+        for (part, answer) in parts {
+            let label = label(format!("{}-{}", day, part));
+            checked += 1;
+            let result = match registry.expected(day, part, hash) {
+                None => {
+                    wrong += 1;
+                    "no answer registered".to_string()
+                }
+                Some(_) if answer.value.is_err() => {
+                    wrong += 1;
+                    answer.to_string()
+                }
+                Some(expected) if answer.value.as_ref() != Ok(&expected.answer) => {
+                    wrong += 1;
+                    format!(
+                        "expected {:?}, got {:?}",
+                        expected.answer,
+                        answer.to_string()
+                    )
+                }
+                Some(Entry {
+                    time: Some(time), ..
+                }) if answer.time > *time * SLOWDOWN_FACTOR
+                    && answer.time > *time + SLOWDOWN_MIN =>
+                {
+                    slower += 1;
+                    format!(
+                        "slower, took {:.2?} but {:.2?} when registered",
+                        answer.time, time
+                    )
+                }
+                Some(_) => format!("ok ({:.2?})", answer.time),
+            };
+            writeln!(out, "{}: {}", label, result).map_err(write_error)?;
+        }
+    }
+
+    writeln!(
+        out,
+        "{} answers checked: {} wrong, {} slower than registered",
+        checked, wrong, slower
+    )
+    .map_err(write_error)?;
+    if wrong > 0 {
+        return Err(format!("{} answers didn't match the registry", wrong));
+    }
+    Ok(())
+}
+
+/// The message a solver panicked with.
+fn panic_message(panic: &(dyn Any + Send)) -> &str {
+    match panic.downcast_ref::<&str>() {
+        Some(message) => message,
+        None => panic
+            .downcast_ref::<String>()
+            .map_or("unknown panic", |message| message.as_str()),
+    }
+}
+
+/// Solves every part, numbering the answers.
+fn all_parts(answers: Answers) -> Vec<(u8, Answer)> {
+    let (part1, part2) = answers.solve_all();
+    std::iter::once((1, part1))
+        .chain(part2.map(|answer| (2, answer)))
+        .collect()
+}
+
+/// Fails if any part failed, after their errors have been written with the other answers.
+fn check_parts(parts: &[(u8, Answer)]) -> Result<(), String> {
+    let failed = parts.iter().filter(|(_, answer)| answer.value.is_err());
+    match failed.count() {
+        0 => Ok(()),
+        failed => Err(format!("{} parts failed", failed)),
+    }
+}
+
+/// Writes an answer on one line, or below the day and part if it's a picture.
+fn write_answer(out: &mut dyn Write, day: u8, part: u8, answer: &Answer) -> std::io::Result<()> {
+    if matches!(&answer.value, Ok(value) if value.contains('\n')) {
+        writeln!(
+            out,
+            "Day {}-{} ({:.2?}):\n{}",
+            day, part, answer.time, answer
+        )
+    } else {
+        writeln!(
+            out,
+            "Day {}-{}: {} ({:.2?})",
+            day, part, answer, answer.time
+        )
+    }
+}
+
+fn day_json(day: u8, parts: &[(u8, Answer)]) -> String {
+    let parts: Vec<String> = parts
+        .iter()
+        .map(|(part, answer)| {
+            let value = match &answer.value {
+                Ok(value) => format!("\"answer\":{}", json_string(value)),
+                Err(e) => format!("\"error\":{}", json_string(e)),
+            };
+            format!(
+                "{{\"part\":{},{},\"time_ms\":{:.3}}}",
+                part,
+                value,
+                answer.time.as_secs_f64() * 1000.0
+            )
+        })
+        .collect();
+    format!("{{\"day\":{},\"parts\":[{}]}}", day, parts.join(","))
+}
+
+fn json_string(s: &str) -> String {
+    let mut json = String::from("\"");
+    for c in s.chars() {
+        match c {
+            '"' => json.push_str("\\\""),
+            '\\' => json.push_str("\\\\"),
+            '\n' => json.push_str("\\n"),
+            '\r' => json.push_str("\\r"),
+            '\t' => json.push_str("\\t"),
+            c if (c as u32) < 0x20 => json.push_str(&format!("\\u{:04x}", c as u32)),
+            c => json.push(c),
+        }
+    }
+    json.push('"');
+    json
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::*;
+
+    fn args(s: &str) -> Vec<String> {
+        s.split(' ').map(|it| it.to_string()).collect()
+    }
+
+    fn run_with(options: &str) -> Result<String, String> {
+        let options = Options::parse(&args(options)).unwrap();
+        let mut out = Vec::new();
+        run(&options, &mut out)?;
+        Ok(String::from_utf8(out).unwrap())
+    }
+
+    #[test]
+    fn test_parse_options() {
+        assert_eq!(
+            Options::parse(&args("run 9 --part 2 --input in.txt --json")).unwrap(),
+            Options {
+                command: Command::Run {
+                    day: 9,
+                    part: Some(2),
+                    input_path: Some("in.txt".to_string()),
+                },
+                json: true,
+            }
+        );
+        assert_eq!(
+            Options::parse(&args("all")).unwrap(),
+            Options {
+                command: Command::All,
+                json: false,
+            }
+        );
+    }
+
+    #[test]
+    fn test_parse_bad_options() {
+        assert!(Options::parse(&args("run")).is_err());
+        assert!(Options::parse(&args("run 20")).is_err());
+        assert!(Options::parse(&args("run 1 --part 3")).is_err());
+        assert!(Options::parse(&args("run 1 2")).is_err());
+        assert!(Options::parse(&args("all --part 1")).is_err());
+        assert!(Options::parse(&args("walk 1")).is_err());
+        assert!(Options::parse(&args("all --fast")).is_err());
+        assert!(Options::parse(&args("all --registry r.txt")).is_err());
+        assert!(Options::parse(&args("verify --json")).is_err());
+        assert!(Options::parse(&args("register")).is_err());
+        assert!(Options::parse(&args("register 1 --part 1")).is_err());
+    }
+
+    #[test]
+    fn test_parse_verify_and_register() {
+        assert_eq!(
+            Options::parse(&args("verify --registry r.txt a.txt b.txt"))
+                .unwrap()
+                .command,
+            Command::Verify {
+                registry_path: Some("r.txt".to_string()),
+                input_paths: vec!["a.txt".to_string(), "b.txt".to_string()],
+            }
+        );
+        assert_eq!(
+            Options::parse(&args("register 9 --input in.txt"))
+                .unwrap()
+                .command,
+            Command::Register {
+                day: 9,
+                input_path: Some("in.txt".to_string()),
+            }
+        );
+    }
+
+    #[test]
+    fn test_registry_covers_every_day() {
+        let registry = Registry::parse(REGISTRY).unwrap();
+        for (day, _, input) in DAYS.iter() {
+            let hash = input_hash(input);
+            assert!(registry.expected(*day, 1, hash).is_some(), "day {}", day);
+            assert_eq!(
+                registry.expected(*day, 2, hash).is_some(),
+                *day != 22 && *day != 25,
+                "day {}",
+                day
+            );
+        }
+    }
+
+    #[test]
+    fn test_every_day_matches_registry() {
+        let registry = Registry::parse(REGISTRY).unwrap();
+        let mut out = Vec::new();
+        let result = verify(&registry, DAYS, &[], &mut out);
+        let out = String::from_utf8(out).unwrap();
+        assert!(result.is_ok(), "{}", out);
+    }
+
+    fn count_lines(input: &str) -> Result<Answers<'_>, String> {
+        Ok(Answers::new(
+            move || Ok(input.lines().count()),
+            move || Ok(input.trim().to_uppercase()),
+        ))
+    }
+
+    #[test]
+    fn test_verify() {
+        let days: &[(u8, Solver, &str)] = &[(1, count_lines, "a\nb\n")];
+        let registry = |part2: &str, time: &str| {
+            let hash = input_hash("a\nb");
+            let text = format!(
+                "1-1 {:x} - \"2\"\n1-2 {:x} {} \"{}\"\n1-1 {:x} - \"1\"\n1-2 {:x} - \"X\"",
+                hash,
+                hash,
+                time,
+                part2,
+                input_hash("x"),
+                input_hash("x")
+            );
+            Registry::parse(&text).unwrap()
+        };
+        let verify_with = |registry: &Registry, inputs: &[(String, String)]| {
+            let mut out = Vec::new();
+            let result = verify(registry, days, inputs, &mut out);
+            (result, String::from_utf8(out).unwrap())
+        };
+
+        let (result, out) = verify_with(&registry("A\\nB", "-"), &[]);
+        assert!(result.is_ok(), "{}", out);
+        assert!(out.starts_with("Day 1-1: ok ("), "{}", out);
+        assert!(out.ends_with("2 answers checked: 0 wrong, 0 slower than registered\n"));
+
+        let (result, out) = verify_with(&registry("A\\nC", "-"), &[]);
+        assert_eq!(result.unwrap_err(), "1 answers didn't match the registry");
+        assert!(
+            out.contains("Day 1-2: expected \"A\\nC\", got \"A\\nB\""),
+            "{}",
+            out
+        );
+
+        // registered as taking no time at all, but it's not slower by enough to matter
+        let (result, out) = verify_with(&registry("A\\nB", "0"), &[]);
+        assert!(result.is_ok());
+        assert!(out.contains("0 slower"), "{}", out);
+
+        // other inputs are checked for the days they're registered for
+        let inputs = vec![
+            ("x.txt".to_string(), "x\n".to_string()),
+            ("y.txt".to_string(), "y\n".to_string()),
+        ];
+        let (result, out) = verify_with(&registry("A\\nB", "-"), &inputs);
+        assert!(out.contains("Day 1-1 (x.txt): ok ("), "{}", out);
+        assert!(out.contains("Day 1-2 (x.txt): ok ("), "{}", out);
+        assert!(out.contains("y.txt: no answers registered for this input"));
+        assert_eq!(result.unwrap_err(), "1 answers didn't match the registry");
+    }
+
+    fn bad_input(_: &str) -> Result<Answers<'_>, String> {
+        Err("bad input".to_string())
+    }
+
+    fn solver_bug(_: &str) -> Result<Answers<'_>, String> {
+        Ok(Answers::new(
+            || -> Result<i32, _> { panic!("solver bug") },
+            || Ok(2),
+        ))
+    }
+
+    #[test]
+    fn test_verify_failing_days() {
+        let days: &[(u8, Solver, &str)] = &[
+            (2, bad_input, "x"),
+            (3, solver_bug, "y"),
+            (1, count_lines, "a\nb\n"),
+        ];
+        let hash = input_hash("a\nb");
+        let text = format!("1-1 {:x} - \"2\"\n1-2 {:x} - \"A\\nB\"", hash, hash);
+        let registry = Registry::parse(&text).unwrap();
+        let mut out = Vec::new();
+        let result = verify(&registry, days, &[], &mut out);
+        let out = String::from_utf8(out).unwrap();
+
+        assert_eq!(result.unwrap_err(), "2 answers didn't match the registry");
+        assert!(out.starts_with("Day 2: failed: bad input\n"), "{}", out);
+        assert!(out.contains("Day 3: panicked: solver bug\n"), "{}", out);
+        assert!(out.contains("Day 1-1: ok ("), "{}", out);
+        assert!(out.ends_with("2 answers checked: 2 wrong, 0 slower than registered\n"));
+    }
+
+    #[test]
+    fn test_run_day() {
+        let out = run_with("run 1").unwrap();
+        let lines: Vec<&str> = out.lines().collect();
+        assert_eq!(lines.len(), 2);
+        assert!(lines[0].starts_with("Day 1-1: 3514064 ("), "{}", out);
+        assert!(lines[1].starts_with("Day 1-2: 5268207 ("), "{}", out);
+
+        let out = run_with("run 1 --part 2 --json").unwrap();
+        assert!(
+            out.starts_with(
+                "{\"day\":1,\"parts\":[{\"part\":2,\"answer\":\"5268207\",\"time_ms\":"
+            ),
+            "{}",
+            out
+        );
+
+        assert_eq!(
+            run_with("run 22 --part 2").unwrap_err(),
+            "Day 22 has no part 2"
+        );
+    }
+
+    #[test]
+    fn test_run_day_with_input() {
+        let path = std::env::temp_dir().join("aoc-test-day1.txt");
+        std::fs::write(&path, "1969\n").unwrap();
+        let out = run_with(&format!("run 1 --input {}", path.display())).unwrap();
+        assert!(out.starts_with("Day 1-1: 654 ("), "{}", out);
+        assert!(out.contains("Day 1-2: 966 ("), "{}", out);
+        std::fs::remove_file(&path).unwrap();
+
+        assert!(run_with("run 1 --input /nonexistent/input.txt")
+            .unwrap_err()
+            .starts_with("Can't read '/nonexistent/input.txt'"));
+    }
+
+    #[test]
+    fn test_run_day_with_bad_input() {
+        let path = std::env::temp_dir().join("aoc-test-day9.txt");
+        std::fs::write(&path, "hello\n").unwrap();
+        let error = run_with(&format!("run 9 --input {}", path.display())).unwrap_err();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(
+            error,
+            "Can't parse intcode: line 1, column 1: 'hello' is not an integer"
+        );
+    }
+
+    #[test]
+    fn test_run_day_with_failed_part() {
+        // YOU and SAN orbit objects that don't orbit each other
+        let path = std::env::temp_dir().join("aoc-test-day6.txt");
+        std::fs::write(&path, "COM)YOU\nX)SAN\n").unwrap();
+        let mut out = Vec::new();
+        let options = Options::parse(&args(&format!("run 6 --input {}", path.display()))).unwrap();
+        let result = run(&options, &mut out);
+        let json_options = Options::parse(&args(&format!(
+            "run 6 --part 2 --input {} --json",
+            path.display()
+        )))
+        .unwrap();
+        let mut json = Vec::new();
+        let json_result = run(&json_options, &mut json);
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(result.unwrap_err(), "1 parts failed");
+        let out = String::from_utf8(out).unwrap();
+        assert!(out.starts_with("Day 6-1: 2 ("), "{}", out);
+        assert!(
+            out.contains("Day 6-2: failed: YOU and SAN can never reach each other :( ("),
+            "{}",
+            out
+        );
+        assert!(json_result.is_err());
+        let json = String::from_utf8(json).unwrap();
+        assert!(
+            json.starts_with("{\"day\":6,\"parts\":[{\"part\":2,\"error\":\"YOU and SAN"),
+            "{}",
+            json
+        );
+    }
+
+    #[test]
+    fn test_json_string() {
+        assert_eq!(json_string("42"), "\"42\"");
+        assert_eq!(
+            json_string("#  #\n\"a\\b\"\u{1}"),
+            "\"#  #\\n\\\"a\\\\b\\\"\\u0001\""
+        );
+    }
+}
@@ -0,0 +1,263 @@
+//! The expected answers to every day's puzzle, to check that the solvers still get them after a
+//! change, e.g. to `intcode`. Answers are registered by a hash of the input they're for, so anyone's
+//! puzzle input can be registered alongside the bundled ones.
+//!
+//! The text format has one answer per line: the day and part, the input's hash, how long the part
+//! took to solve in milliseconds (or `-` to not check the time) and the answer, quoted with `\n`,
+//! `\"` and `\\` escaped. Blank lines and lines starting with `#` are ignored.
+//!
+//! ```text
+//! # day-part  input             ms       answer
+//! 1-1         f66714c56212da48  0.008    "3514064"
+//! ```
+
+use std::fmt::{Display, Formatter};
+use std::time::Duration;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Entry {
+    pub day: u8,
+    pub part: u8,
+    pub input_hash: u64,
+    /// How long it took to solve when it was registered, in a release build.
+    pub time: Option<Duration>,
+    pub answer: String,
+}
+
+impl Display for Entry {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), std::fmt::Error> {
+        let time = match self.time {
+            Some(time) => format!("{:.3}", time.as_secs_f64() * 1000.0),
+            None => "-".to_string(),
+        };
+        write!(
+            f,
+            "{:<11} {:016x}  {:<8} {}",
+            format!("{}-{}", self.day, self.part),
+            self.input_hash,
+            time,
+            quote(&self.answer)
+        )
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Registry {
+    entries: Vec<Entry>,
+}
+
+impl Registry {
+    /// The registered answer to a part of a day's puzzle for the input with the given hash.
+    pub fn expected(&self, day: u8, part: u8, input_hash: u64) -> Option<&Entry> {
+        self.entries
+            .iter()
+            .find(|entry| entry.day == day && entry.part == part && entry.input_hash == input_hash)
+    }
+
+    /// The days that the input with the given hash has answers registered for.
+    pub fn days_for(&self, input_hash: u64) -> Vec<u8> {
+        let mut days: Vec<u8> = self
+            .entries
+            .iter()
+            .filter(|entry| entry.input_hash == input_hash)
+            .map(|entry| entry.day)
+            .collect();
+        days.sort_unstable();
+        days.dedup();
+        days
+    }
+
+    pub fn parse(text: &str) -> Result<Registry, RegistryParseError> {
+        let mut entries = Vec::new();
+        for (line_i, line) in text.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let bad_line = || RegistryParseError {
+                line: line_i + 1,
+                text: line.to_string(),
+            };
+            // the answer is the rest of the line, since it can contain spaces
+            let mut rest = line;
+            let mut field = || {
+                let field_end = rest.find(char::is_whitespace).unwrap_or(rest.len());
+                let field = &rest[..field_end];
+                rest = rest[field_end..].trim_start();
+                if field.is_empty() {
+                    Err(bad_line())
+                } else {
+                    Ok(field)
+                }
+            };
+            let (day, part) = field()?.split_once('-').ok_or_else(bad_line)?;
+            let day = day.parse::<u8>().map_err(|_| bad_line())?;
+            let part = part.parse::<u8>().map_err(|_| bad_line())?;
+            let input_hash = u64::from_str_radix(field()?, 16).map_err(|_| bad_line())?;
+            let time = match field()? {
+                "-" => None,
+                ms => {
+                    let ms = ms
+                        .parse::<f64>()
+                        .ok()
+                        .filter(|ms| *ms >= 0.0 && ms.is_finite())
+                        .ok_or_else(bad_line)?;
+                    Some(Duration::from_nanos((ms * 1_000_000.0).round() as u64))
+                }
+            };
+            let answer = unquote(rest).ok_or_else(bad_line)?;
+            entries.push(Entry {
+                day,
+                part,
+                input_hash,
+                time,
+                answer,
+            });
+        }
+        Ok(Registry { entries })
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub struct RegistryParseError {
+    pub line: usize,
+    pub text: String,
+}
+
+impl Display for RegistryParseError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), std::fmt::Error> {
+        write!(
+            f,
+            "line {}: expected a day-part, an input hash, a time and a quoted answer, got '{}'",
+            self.line, self.text
+        )
+    }
+}
+
+impl std::error::Error for RegistryParseError {}
+
+/// A hash of a puzzle input that doesn't change between runs or Rust versions, ignoring trailing
+/// whitespace so it doesn't matter whether the file ends with a newline (64-bit FNV-1a).
+pub fn input_hash(input: &str) -> u64 {
+    input
+        .trim_end()
+        .bytes()
+        .fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+            (hash ^ byte as u64).wrapping_mul(0x0100_0000_01b3)
+        })
+}
+
+fn quote(s: &str) -> String {
+    let escaped = s
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n");
+    format!("\"{}\"", escaped)
+}
+
+fn unquote(s: &str) -> Option<String> {
+    let s = s.strip_prefix('"')?.strip_suffix('"')?;
+    let mut unquoted = String::new();
+    let mut chars = s.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => match chars.next()? {
+                'n' => unquoted.push('\n'),
+                c @ '"' | c @ '\\' => unquoted.push(c),
+                _ => return None,
+            },
+            '"' => return None,
+            c => unquoted.push(c),
+        }
+    }
+    Some(unquoted)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::registry::{input_hash, Entry, Registry, RegistryParseError};
+    use std::time::Duration;
+
+    #[test]
+    fn test_input_hash() {
+        // the FNV-1a test vectors
+        assert_eq!(input_hash(""), 0xcbf2_9ce4_8422_2325);
+        assert_eq!(input_hash("a"), 0xaf63_dc4c_8601_ec8c);
+        assert_eq!(input_hash("a\n"), input_hash("a"));
+        assert_ne!(input_hash("1\n2"), input_hash("1\n3"));
+    }
+
+    #[test]
+    fn test_parse_and_lookup() {
+        let registry = Registry::parse(
+            "# day-part  input  ms  answer\n\
+             \n\
+             1-1 00000000000000ff 0.5 \"3514064\"\n\
+             1-2 00000000000000ff - \"5268207\"\n\
+             11-2 abc 120 \" #\\n# \\\"\\\\\"\n",
+        )
+        .unwrap();
+        assert_eq!(registry.entries.len(), 3);
+        assert_eq!(registry.expected(1, 1, 0xff).unwrap().answer, "3514064");
+        assert_eq!(
+            registry.expected(1, 1, 0xff).unwrap().time,
+            Some(Duration::from_micros(500))
+        );
+        assert_eq!(registry.expected(1, 2, 0xff).unwrap().time, None);
+        assert_eq!(registry.expected(1, 1, 0xfe), None);
+        assert_eq!(
+            registry.expected(11, 2, 0xabc).unwrap().answer,
+            " #\n# \"\\"
+        );
+        assert_eq!(registry.days_for(0xff), vec![1]);
+        assert!(registry.days_for(0).is_empty());
+
+        // round trip through the text format
+        let text: String = registry
+            .entries
+            .iter()
+            .map(|entry| format!("{}\n", entry))
+            .collect();
+        assert_eq!(Registry::parse(&text).unwrap(), registry);
+    }
+
+    #[test]
+    fn test_entry_format() {
+        let entry = Entry {
+            day: 1,
+            part: 1,
+            input_hash: 0xce3a_4fa8_7b1e_2c9d,
+            time: Some(Duration::from_micros(52)),
+            answer: "3514064".to_string(),
+        };
+        assert_eq!(
+            entry.to_string(),
+            "1-1         ce3a4fa87b1e2c9d  0.052    \"3514064\""
+        );
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        for (text, line) in [
+            ("1-1 ff 1 \"a\"\n1 ff 1 \"a\"", 2),
+            ("1-1 xyz 1 \"a\"", 1),
+            ("1-1 ff fast \"a\"", 1),
+            ("1-1 ff 1 a", 1),
+            ("1-1 ff 1 \"a\\t\"", 1),
+            ("1-1 ff 1", 1),
+        ]
+        .iter()
+        {
+            let error = Registry::parse(text).unwrap_err();
+            assert_eq!(error.line, *line, "{}", text);
+        }
+        assert_eq!(
+            Registry::parse("1-1 ff -1 \"a\"").unwrap_err(),
+            RegistryParseError {
+                line: 1,
+                text: "1-1 ff -1 \"a\"".to_string(),
+            }
+        );
+    }
+}
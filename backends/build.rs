@@ -0,0 +1,73 @@
+use intcode::codegen::generate_rust;
+use intcode::parse_intcode_input;
+use std::env;
+use std::fmt::Write;
+use std::fs;
+use std::path::Path;
+
+/// The days whose puzzle input is an intcode program.
+const DAYS: &[u8] = &[2, 5, 7, 9, 11, 13, 15, 17, 19, 21, 23, 25];
+
+/// Translates every day's program and the programs in faults.txt to Rust, each in a module of its
+/// own, along with tables of them for the tests to go through.
+fn main() {
+    let mut source = String::new();
+
+    println!("cargo:rerun-if-changed=faults.txt");
+    let mut faults = String::new();
+    for line in fs::read_to_string("faults.txt").unwrap().lines() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let (name, intcode) = line.split_once(' ').unwrap();
+        let intcode = intcode.trim();
+        write_module(&mut source, name, intcode);
+        writeln!(
+            faults,
+            "    ({:?}, {:?}, {}::program),",
+            name, intcode, name
+        )
+        .unwrap();
+    }
+    write_table(&mut source, "FAULTS", "&str", &faults);
+
+    let mut days = String::new();
+    for day in DAYS {
+        let path = format!("../day{0}/day{0}.txt", day);
+        println!("cargo:rerun-if-changed={}", path);
+        let intcode = fs::read_to_string(&path).unwrap();
+        let name = format!("day{}", day);
+        write_module(&mut source, &name, intcode.trim());
+        writeln!(
+            days,
+            "    ({}, {:?}, {}::program),",
+            day,
+            intcode.trim(),
+            name
+        )
+        .unwrap();
+    }
+    write_table(&mut source, "DAYS", "u8", &days);
+
+    let out_dir = env::var("OUT_DIR").unwrap();
+    fs::write(Path::new(&out_dir).join("programs.rs"), source).unwrap();
+}
+
+fn write_module(source: &mut String, name: &str, intcode: &str) {
+    writeln!(source, "pub mod {} {{", name).unwrap();
+    source.push_str(&generate_rust(&parse_intcode_input(intcode)));
+    writeln!(source, "}}").unwrap();
+}
+
+/// A table of (`key`, intcode, `program` function) for each program.
+fn write_table(source: &mut String, name: &str, key: &str, rows: &str) {
+    writeln!(
+        source,
+        "pub const {}: &[({}, &str, crate::ProgramFn)] = &[",
+        name, key
+    )
+    .unwrap();
+    source.push_str(rows);
+    writeln!(source, "];").unwrap();
+}
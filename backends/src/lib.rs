@@ -0,0 +1,8 @@
+//! Every day's program, and some that fail in every way an instruction can, translated to Rust by
+//! the build script for `intcode`'s tests of the compiled backend. It's only a dev-dependency of
+//! `intcode` and not a workspace member, so the programs are only translated when testing.
+
+/// Creates a compiled program, ready to run.
+pub type ProgramFn = fn() -> intcode::compiled::CompiledProgram;
+
+include!(concat!(env!("OUT_DIR"), "/programs.rs"));
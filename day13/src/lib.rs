@@ -0,0 +1,192 @@
+use answers::Answers;
+use intcode::symbols::SymbolTable;
+use intcode::{check_intcode_input, parse_intcode_input, IntcodeProgram};
+use std::io;
+
+pub const INPUT: &str = include_str!("../day13.txt");
+const GAME_SYMBOLS: &str = include_str!("../day13.symbols");
+
+const SCREEN_WIDTH: usize = 45;
+const SCREEN_HEIGHT: usize = 24;
+const TILE_EMPTY: i64 = 0;
+const TILE_WALL: i64 = 1; // indestructible
+const TILE_BLOCK: i64 = 2; // can be broken by ball
+const TILE_HORIZ_PADDLE: i64 = 3; // indestructible
+const TILE_BALL: i64 = 4; // moves and bounces off of things
+
+const NUM_QTRS: i64 = 2;
+
+const JOY_NEUT: i64 = 0;
+const JOY_LEFT: i64 = -1;
+const JOY_RIGHT: i64 = 1;
+
+pub fn solve(input: &str) -> Result<Answers<'_>, String> {
+    check_intcode_input(input)?;
+    Ok(Answers::new(
+        move || Ok(run_game(input, true, false).0),
+        move || {
+            check_game_symbols(input)?;
+            Ok(run_game(input, true, true).1)
+        },
+    ))
+}
+
+/// Checks that the instruction `run_game` hacks to win is where day13.symbols says it is. The
+/// symbols were found in the bundled game, so another game may have it somewhere else.
+pub fn check_game_symbols(input: &str) -> Result<(), String> {
+    let memory = parse_intcode_input(input);
+    let symbols = SymbolTable::parse(GAME_SYMBOLS).unwrap();
+    match memory.get(symbols.address("check_ball_y").unwrap()) {
+        Some(1007) => Ok(()),
+        _ => Err(
+            "day13.symbols doesn't match this game, so it can't be hacked to win; the symbols \
+             are only for the bundled game"
+                .to_string(),
+        ),
+    }
+}
+
+/// Plays the game, drawing the screen after every move unless `headless`. Without
+/// `cheat_to_win`, the joystick is moved with `a`, `s` and `d` on stdin. Cheating needs a game
+/// that `check_game_symbols` accepts.
+pub fn run_game(input: &str, headless: bool, cheat_to_win: bool) -> (i64, i64) {
+    // blocks remaining, score
+    let mut memory = parse_intcode_input(input);
+    let symbols = SymbolTable::parse(GAME_SYMBOLS).unwrap();
+
+    if cheat_to_win {
+        memory[symbols.address("quarters").unwrap()] = NUM_QTRS;
+
+        // And since we're modifying memory to play for free, anyway...
+        //
+        // There's one instruction each loop to check whether the ball's y coordinate is past the
+        // bottom of the screen. Hack the program to pretend that it never is:
+        //
+        // memory[365..=368] = 1007,389,23,381 => LT m[389], 23 -> m[381]
+        // >> change to........1107,0,23,381   => LT 0, 23 -> m[381] => 1 -> m[381]
+        //
+        // If we store 0 in memory[381] each time this comparison executes, the game ignores the
+        // paddle position until we reach the win condition.
+        let check_ball_y = symbols.address("check_ball_y").unwrap();
+        assert_eq!(
+            memory[check_ball_y], 1007,
+            "day13.symbols doesn't match this game, it's only for the bundled one"
+        );
+        memory[check_ball_y] = 1107;
+        memory[check_ball_y + 1] = 0;
+    }
+
+    let mut program = IntcodeProgram::init(&memory, Vec::new());
+
+    let mut player_score: i64 = 0;
+    let mut game_grid = vec![vec![TILE_EMPTY; SCREEN_WIDTH]; SCREEN_HEIGHT];
+
+    let mut blocks_remaining = 0;
+
+    while !program.is_halted() {
+        program.run();
+
+        // x, y, tile id
+        // consume any outputs first, until they're exhausted
+        while let (Some(x), Some(y), Some(value)) = (
+            program.consume_output(),
+            program.consume_output(),
+            program.consume_output(),
+        ) {
+            match (x, y) {
+                (-1, 0) => {
+                    // segment display
+                    player_score = value;
+                }
+                _ => {
+                    // tile
+                    let old_tile = &mut game_grid[y as usize][x as usize];
+                    if value == TILE_BLOCK {
+                        blocks_remaining += 1;
+                    } else if value != TILE_BLOCK && *old_tile == TILE_BLOCK {
+                        blocks_remaining -= 1;
+                    }
+                    *old_tile = value;
+                }
+            }
+        }
+
+        if !headless {
+            for y in 0..SCREEN_HEIGHT {
+                for x in 0..SCREEN_WIDTH {
+                    let grid_it = game_grid[y][x];
+                    let c = match grid_it {
+                        TILE_EMPTY => ' ',
+                        TILE_WALL => '|',
+                        TILE_BLOCK => '#',
+                        TILE_HORIZ_PADDLE => '_',
+                        TILE_BALL => '*',
+                        _ => panic!("Bad tile"),
+                    };
+                    print!("{}", c)
+                }
+                println!();
+            }
+            println!("BLOCKS REMAINING: {}", blocks_remaining);
+            println!("SCORE: {}", player_score);
+        }
+
+        // provide updated input when requested
+        if program.is_awaiting_input() {
+            let input_val = if cheat_to_win {
+                JOY_NEUT
+            } else {
+                let mut buf: String = String::new();
+                io::stdin().read_line(&mut buf).unwrap();
+                let ch = buf.trim().chars().next();
+                as_input_num(ch.unwrap())
+            };
+            program.buffer_input(input_val);
+        }
+    }
+
+    (blocks_remaining, player_score)
+}
+
+fn as_input_num(ch: char) -> i64 {
+    match ch {
+        'a' => JOY_LEFT,
+        'd' => JOY_RIGHT,
+        's' => JOY_NEUT,
+        _ => panic!("bad input!!!"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{solve, GAME_SYMBOLS, INPUT};
+    use intcode::parse_intcode_input;
+    use intcode::symbols::SymbolTable;
+
+    #[test]
+    fn test_symbols_match_program() {
+        let memory = parse_intcode_input(INPUT);
+        let symbols = SymbolTable::parse(GAME_SYMBOLS).unwrap();
+        let address = |name| symbols.address(name).unwrap() as i64;
+
+        // LT m[ball_y], 23 -> m[ball_in_play]
+        let check_ball_y = address("check_ball_y") as usize;
+        assert_eq!(
+            memory[check_ball_y..check_ball_y + 4].to_vec(),
+            vec![1007, address("ball_y"), 23, address("ball_in_play")]
+        );
+        assert_eq!(symbols.name(check_ball_y + 2), "check_ball_y+2");
+    }
+
+    #[test]
+    fn test_other_game_cant_be_hacked() {
+        let mut memory = parse_intcode_input(INPUT);
+        memory[365] = 1008;
+        let words: Vec<String> = memory.iter().map(|word| word.to_string()).collect();
+        let input = words.join(",");
+        let error = solve(&input).unwrap().part(2).unwrap().value.unwrap_err();
+        assert!(error.starts_with("day13.symbols doesn't match this game"));
+
+        assert!(solve("99").unwrap().part(2).unwrap().value.is_err());
+    }
+}
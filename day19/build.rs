@@ -0,0 +1,15 @@
+use intcode::codegen::generate_rust;
+use intcode::parse_intcode_input;
+use std::env;
+use std::fs;
+use std::path::Path;
+
+/// Translates the drone program to Rust ahead of time, since part 2 runs it thousands of times.
+fn main() {
+    println!("cargo:rerun-if-changed=day19.txt");
+
+    let drone_program = fs::read_to_string("day19.txt").unwrap();
+    let source = generate_rust(&parse_intcode_input(&drone_program));
+    let out_dir = env::var("OUT_DIR").unwrap();
+    fs::write(Path::new(&out_dir).join("drone.rs"), source).unwrap();
+}
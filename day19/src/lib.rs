@@ -0,0 +1,187 @@
+use answers::Answers;
+use intcode::machine::IntcodeMachine;
+use intcode::{try_parse_intcode_input, IntcodeProgram};
+
+/// The drone program, translated to Rust by the build script.
+mod drone {
+    include!(concat!(env!("OUT_DIR"), "/drone.rs"));
+}
+
+pub const INPUT: &str = include_str!("../day19.txt");
+
+const PT1_GRID_SIZE: usize = 50;
+
+pub fn solve(input: &str) -> Result<Answers<'_>, String> {
+    let drone = Drone::new(input)?;
+    let pt2_drone = drone.clone();
+    Ok(Answers::new(
+        move || Ok(part1(&drone)),
+        move || Ok(part2(&pt2_drone)),
+    ))
+}
+
+/// The drone program to run for an input. Only the bundled program was translated, any other one
+/// has to be interpreted. They're compared parsed, so e.g. line endings don't matter.
+#[derive(Clone)]
+enum Drone {
+    Translated,
+    Interpreted(Vec<i64>),
+}
+
+impl Drone {
+    fn new(input: &str) -> Result<Drone, String> {
+        let memory = try_parse_intcode_input(input)?;
+        if memory == drone::IMAGE {
+            Ok(Drone::Translated)
+        } else {
+            Ok(Drone::Interpreted(memory))
+        }
+    }
+}
+
+fn part1(drone: &Drone) -> usize {
+    let mut count = 0;
+    for x in 0..PT1_GRID_SIZE {
+        for y in 0..PT1_GRID_SIZE {
+            if run_program(drone, x, y) {
+                count += 1;
+            }
+        }
+    }
+    count
+}
+
+const PT2_DIAG_COUNT_GOAL: usize = 100;
+
+fn part2(drone: &Drone) -> usize {
+    let mut start_x = 200;
+
+    loop {
+        let (count, result) = get_santa_units_at_fixed_x(drone, start_x);
+        if count == PT2_DIAG_COUNT_GOAL {
+            return result;
+        }
+
+        // made up heuristic for stepping more quickly through the options, but not great since it
+        // requires that I know how the beam looks...but I do, so ¯\_(ツ)_/¯
+        if PT2_DIAG_COUNT_GOAL - count >= 5 {
+            start_x += 100;
+        } else {
+            start_x += 1;
+        }
+    }
+}
+
+fn get_santa_units_at_fixed_x(drone: &Drone, start_x: usize) -> (usize, usize) {
+    let mut y_top = start_x;
+    while !run_program(drone, start_x, y_top) {
+        // First, for fixed x, find the y coord where the beam first has an effect. This is our
+        // potential top right corner of the area containing Santa's ship.
+        y_top += 1;
+    }
+
+    let mut x = start_x;
+    let mut y = y_top;
+    let mut count = 0;
+    loop {
+        let in_beam = run_program(drone, x, y);
+        if in_beam {
+            count += 1;
+        }
+
+        if count == PT2_DIAG_COUNT_GOAL {
+            break;
+        }
+
+        if !in_beam {
+            // out of the beam before reaching 100, this isn't the answer
+            return (count, 0);
+        } else {
+            // Decrease x and increase y, looking for a diagonal count of 100 to signal that Santa's
+            // ship can fit. This is the simplest definition of "fitting" that I could think of,
+            // given that the ship is square.
+            x -= 1;
+            y += 1;
+        }
+    }
+
+    (count, x * 10000 + y_top)
+}
+
+fn run_program(drone: &Drone, x: usize, y: usize) -> bool {
+    let mut program: Box<dyn IntcodeMachine> = match drone {
+        Drone::Translated => Box::new(drone::program()),
+        Drone::Interpreted(memory) => Box::new(IntcodeProgram::init(memory, Vec::new())),
+    };
+
+    program.buffer_input(x as i64);
+    program.buffer_input(y as i64);
+    program.run();
+
+    if let Some(output) = program.consume_output() {
+        if output == 1 {
+            return true;
+        }
+    }
+
+    false
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use intcode::differential::lockstep;
+    use intcode::parse_intcode_input;
+    use intcode::taint::TaintTracker;
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    #[test]
+    fn test_translated_input() {
+        let crlf = format!("{}\r\n", INPUT.trim());
+        assert!(matches!(Drone::new(&crlf).unwrap(), Drone::Translated));
+    }
+
+    #[test]
+    fn test_interpreted_input() {
+        let interpreted = Drone::Interpreted(parse_intcode_input(INPUT));
+        assert_eq!(part1(&interpreted), part1(&Drone::Translated))
+    }
+
+    #[test]
+    fn test_compiled_matches_interpreter() {
+        for x in (0..PT1_GRID_SIZE).step_by(5) {
+            for y in (0..PT1_GRID_SIZE).step_by(5) {
+                let mut compiled = drone::program();
+                let mut interpreted = IntcodeProgram::init_from(INPUT);
+                if let Err(divergence) =
+                    lockstep(&mut compiled, &mut interpreted, &[x as i64, y as i64])
+                {
+                    panic!("({}, {}): {}", x, y, divergence);
+                }
+
+                // only the block that rewrites its own jump target needs the interpreter
+                assert!(compiled.interpreted_instructions() < 50);
+            }
+        }
+    }
+
+    #[test]
+    fn test_output_depends_on_both_coordinates() {
+        for (x, y) in [(0, 0), (10, 10), (40, 49)].iter() {
+            let tracker = Rc::new(RefCell::new(TaintTracker::new()));
+            let mut program = IntcodeProgram::init_from(INPUT);
+            program.add_observer(tracker.clone());
+            program.buffer_input(*x);
+            program.buffer_input(*y);
+            program.run();
+
+            let tracker = tracker.borrow();
+            assert_eq!(tracker.outputs().len(), 1);
+            assert_eq!(
+                tracker.outputs()[0].inputs(),
+                vec![0, 1].into_iter().collect()
+            );
+        }
+    }
+}
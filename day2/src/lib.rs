@@ -0,0 +1,59 @@
+extern crate intcode;
+
+use answers::Answers;
+use intcode::{check_intcode_input, parse_intcode_input, IntcodeProgram};
+
+pub const INPUT: &str = include_str!("../day2.txt");
+const NOUN: i64 = 12;
+const VERB: i64 = 2;
+
+pub fn solve(input: &str) -> Result<Answers<'_>, String> {
+    check_intcode_input(input)?;
+    Ok(Answers::new(
+        move || Ok(calc_day2(input, NOUN, VERB)),
+        move || day2_pt2(input).ok_or_else(|| "No answer found!".to_string()),
+    ))
+}
+
+fn day2_pt2(input: &str) -> Option<i64> {
+    for n in 0..99 {
+        for v in 0..99 {
+            let result = calc_day2(input, n, v);
+            if result == 19690720 {
+                return Some(100 * n + v);
+            }
+        }
+    }
+    None
+}
+
+// OPCODE 1: 1,op1,op2,dest; set dest = *op1 + *op2
+// OPCODE 2: 2,op1,op2,dest; set dest = *op1 * *op2
+// OPCODE 99: halt
+// OPCODE anything else: error
+fn calc_day2(input: &str, noun: i64, verb: i64) -> i64 {
+    let mut memory: Vec<i64> = parse_intcode_input(input);
+
+    // restore the state at the time of the elf problem
+    memory[1] = noun;
+    memory[2] = verb;
+
+    let mut intcode = IntcodeProgram::init(&memory, Default::default());
+    intcode.run();
+    if !intcode.is_halted() {
+        panic!("Intcode program did not halt")
+    }
+    return intcode.mem_value(0);
+}
+
+#[cfg(test)]
+mod tests {
+    use intcode::IntcodeProgram;
+
+    #[test]
+    fn test_simple1() {
+        let mut intcode = IntcodeProgram::init_from("1,0,0,0,99");
+        intcode.run();
+        assert_eq!(intcode.mem_value(0), 2);
+    }
+}
@@ -0,0 +1,80 @@
+use answers::Answers;
+use intcode::{check_intcode_input, parse_intcode_input, IntcodeProgram};
+
+pub const INPUT: &str = include_str!("../day21.txt");
+
+const PT1_ROUTINE: &str = "OR A J
+AND B J
+AND C J
+NOT J J
+AND D J
+WALK\n";
+
+const PT2_ROUTINE: &str = "OR  A J
+AND B J
+AND C J
+NOT J J
+AND D J
+OR E T
+OR H T
+AND T J
+RUN\n";
+
+pub fn solve(input: &str) -> Result<Answers<'_>, String> {
+    check_intcode_input(input)?;
+    Ok(Answers::new(
+        move || Ok(run_robot_part1(input)),
+        move || Ok(run_robot_part2(input)),
+    ))
+}
+
+fn run_robot_part1(input: &str) -> i64 {
+    run_robot(input, PT1_ROUTINE).unwrap_or_else(|view| panic!("Robot tragically died\n{}", view))
+}
+
+fn run_robot_part2(input: &str) -> i64 {
+    run_robot(input, PT2_ROUTINE).unwrap_or_else(|view| panic!("Robot tragically died\n{}", view))
+}
+
+// Returns the hull damage, or what the robot printed if it fell into space instead.
+fn run_robot(input: &str, routine: &str) -> Result<i64, String> {
+    let mut inputs: Vec<i64> = Vec::new();
+    add_ascii_inputs(&mut inputs, routine);
+
+    let program_memory = parse_intcode_input(input);
+
+    // init program with all inputs already buffered
+    let mut program = IntcodeProgram::init(&program_memory, inputs);
+    let mut view = String::new();
+    while !program.is_halted() {
+        program.run();
+
+        while let Some(output) = program.consume_output() {
+            let ch = std::char::from_u32(output as u32);
+            if let Some(c) = ch {
+                view.push(c); // robot died
+            } else {
+                return Ok(output); // hull damage
+            }
+        }
+    }
+    Err(view)
+}
+
+fn add_ascii_inputs(input_vec: &mut Vec<i64>, input_str: &str) {
+    input_str
+        .chars()
+        .map(|c| c as i64)
+        .for_each(|ascii_val| input_vec.push(ascii_val));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_robot_falls() {
+        let view = run_robot(INPUT, "WALK\n").unwrap_err();
+        assert!(view.contains("Didn't make it across"), "{}", view);
+    }
+}
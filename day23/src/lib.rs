@@ -0,0 +1,75 @@
+extern crate intcode;
+
+use answers::Answers;
+use intcode::network::{Network, Route, Stop};
+use intcode::{check_intcode_input, IntcodeProgram};
+
+pub const INPUT: &str = include_str!("../day23.txt");
+const NUM_NICS: i64 = 50;
+const NAT_ADDR: i64 = 255;
+
+pub fn solve(input: &str) -> Result<Answers<'_>, String> {
+    check_intcode_input(input)?;
+    Ok(Answers::new(
+        move || Ok(run_network(input, 1)),
+        move || Ok(run_network(input, 2)),
+    ))
+}
+
+fn run_network(input: &str, part_no: u8) -> i64 {
+    let nics = (0..NUM_NICS)
+        .map(|addr| {
+            let mut program = IntcodeProgram::init_from(input);
+            program.buffer_input(addr);
+            program
+        })
+        .collect();
+
+    // NICs read -1 when they have no packets, so they never block waiting for input. Instead, the
+    // network is idle when a round goes by with nothing sent or received.
+    let mut network = Network::new(nics).with_frame_size(3).with_idle_input(-1);
+
+    let mut nat_packet: Option<Packet> = None;
+    let mut last_sent_by_nat: Option<Packet> = None;
+    loop {
+        let stop = network
+            .run(|_, frame| {
+                let (dest_addr, x, y) = (frame[0], frame[1], frame[2]);
+                if dest_addr == NAT_ADDR {
+                    nat_packet = Some(Packet { x, y });
+                    if part_no == 1 {
+                        Route::Stop
+                    } else {
+                        Route::Drop
+                    }
+                } else {
+                    Route::To(dest_addr as usize, vec![x, y])
+                }
+            })
+            .expect("NIC failed");
+
+        let packet = nat_packet.expect("Network stopped before sending to the NAT");
+        match stop {
+            // 0 --> NAT sending
+            Stop::Stopped { .. } => return packet.y,
+            // NAT --> 0 sending
+            Stop::Livelock(_) => {
+                if let Some(last_packet) = last_sent_by_nat {
+                    if packet.y == last_packet.y {
+                        return packet.y;
+                    }
+                }
+
+                last_sent_by_nat = Some(packet);
+                network.send(0, &[packet.x, packet.y]).expect("NIC failed");
+            }
+            stop => panic!("Network stopped unexpectedly: {:?}", stop),
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug)]
+struct Packet {
+    x: i64,
+    y: i64,
+}
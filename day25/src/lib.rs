@@ -0,0 +1,146 @@
+extern crate intcode;
+
+use answers::Answers;
+use intcode::search::{Node, Search};
+use intcode::transcript::{replay, Transcript};
+use intcode::{check_intcode_input, IntcodeProgram};
+use std::collections::BTreeSet;
+
+pub const INPUT: &str = include_str!("../day25.txt");
+const PICK_UP_ALL: &str = include_str!("../pick-up-all.transcript");
+const SAFE_ITEMS: [&str; 8] = [
+    "space law space brochure",
+    "polygon",
+    "astrolabe",
+    "hologram",
+    "prime number",
+    "weather machine",
+    "manifold",
+    "mouse",
+];
+
+pub fn solve(input: &str) -> Result<Answers<'_>, String> {
+    check_intcode_input(input)?;
+    Ok(Answers::part1_only(move || {
+        let message = brute_force_santa_password(input)?;
+        password(&message)
+            .map(|password| password.to_string())
+            .ok_or_else(|| "No password in the message".to_string())
+    }))
+}
+
+fn brute_force_santa_password(input: &str) -> Result<String, String> {
+    let program = &mut IntcodeProgram::init_from(input);
+
+    // replay a recorded session that picks up all items and navigates to the room just before
+    // the security checkpoint
+    let pick_up_all = Transcript::parse(PICK_UP_ALL).expect("Bad transcript");
+    replay(program, &pick_up_all).map_err(|e| format!("Couldn't pick up all items: {}", e))?;
+
+    // security might let us through holding every item; if not, we're sent back next to the
+    // checkpoint and can search for the set of items to drop
+    let outputs = enter_checkpoint(program);
+    if passed_security(&as_ascii(&outputs)) {
+        return Ok(as_ascii(&outputs));
+    }
+    let search = Search::new(drop_another_item, |outputs| {
+        passed_security(&as_ascii(outputs))
+    })
+    .dedup_by(dropped_items);
+    search
+        .bfs(program)
+        .map(|node| as_ascii(&node.outputs))
+        .ok_or_else(|| "Did not find password".to_string())
+}
+
+fn drop_another_item(node: &Node) -> Vec<Vec<i64>> {
+    let dropped = dropped_items(node);
+    SAFE_ITEMS
+        .iter()
+        .filter(|item| !dropped.contains(*item))
+        .map(|item| {
+            // security checkpoint is east of the penultimate room
+            format!("drop {}\neast\n", item)
+                .chars()
+                .map(|c| c as i64)
+                .collect()
+        })
+        .collect()
+}
+
+fn enter_checkpoint(program: &mut IntcodeProgram) -> Vec<i64> {
+    "east\n"
+        .chars()
+        .for_each(|c| program.buffer_input(c as i64));
+    program.run();
+
+    let mut outputs = Vec::new();
+    while let Some(output) = program.consume_output() {
+        outputs.push(output);
+    }
+    outputs
+}
+
+fn dropped_items(node: &Node) -> BTreeSet<&'static str> {
+    SAFE_ITEMS
+        .iter()
+        .filter(|item| {
+            let drop_instr = format!("drop {}\n", item);
+            node.path
+                .iter()
+                .any(|inputs| as_ascii(inputs).starts_with(&drop_instr))
+        })
+        .cloned()
+        .collect()
+}
+
+fn passed_security(output: &str) -> bool {
+    // security complaints seem to either complain that droids on the ship are heavier or
+    // lighter than ours...so when we're in the right place and there is neither complaint,
+    // that should be the password
+    output.contains("Analyzing...") && !(output.contains("heavier") || output.contains("lighter"))
+}
+
+// the message ends "You should be able to get in by typing <password> on the keypad..."
+fn password(message: &str) -> Option<&str> {
+    message
+        .split_whitespace()
+        .find(|word| word.chars().all(|c| c.is_ascii_digit()))
+}
+
+fn as_ascii(values: &[i64]) -> String {
+    values
+        .iter()
+        .map(|v| std::char::from_u32(*v as u32).unwrap())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{as_ascii, enter_checkpoint, passed_security, solve, INPUT, PICK_UP_ALL};
+    use intcode::transcript::{replay, Transcript};
+    use intcode::IntcodeProgram;
+
+    #[test]
+    fn test_checkpoint_with_all_items() {
+        let program = &mut IntcodeProgram::init_from(INPUT);
+        replay(program, &Transcript::parse(PICK_UP_ALL).unwrap()).unwrap();
+
+        // the search starts from here, so holding every item has to be checked too
+        let output = as_ascii(&enter_checkpoint(program));
+        assert!(output.contains("Analyzing..."));
+        assert!(!passed_security(&output));
+    }
+
+    #[test]
+    fn test_other_program() {
+        // prints "?" and halts, instead of playing the game
+        let answer = solve("104,63,99").unwrap().part(1).unwrap();
+        let error = answer.value.unwrap_err();
+        assert!(
+            error.starts_with("Couldn't pick up all items: diverged"),
+            "{}",
+            error
+        );
+    }
+}
@@ -0,0 +1,54 @@
+extern crate intcode;
+
+use answers::Answers;
+use intcode::instruction_set::InstructionSet;
+use intcode::{check_intcode_input, IntcodeProgram};
+
+pub const INPUT: &str = include_str!("../day5.txt");
+
+/// The system IDs of the air conditioner and the thermal radiator controller.
+const AIR_CONDITIONER: i64 = 1;
+const THERMAL_RADIATOR_CONTROLLER: i64 = 5;
+
+pub fn solve(input: &str) -> Result<Answers<'_>, String> {
+    check_intcode_input(input)?;
+    Ok(Answers::new(
+        move || Ok(diagnostic_code(input, AIR_CONDITIONER)),
+        move || Ok(diagnostic_code(input, THERMAL_RADIATOR_CONTROLLER)),
+    ))
+}
+
+/// Runs the diagnostic program with `system_id` as its input, returning its diagnostic code.
+fn diagnostic_code(input: &str, system_id: i64) -> i64 {
+    let mut outputs = Vec::new();
+    run_diagnostic(input, || Ok(system_id), |output| outputs.push(output))
+        .unwrap_or_else(|e| panic!("Diagnostic failed: {}", e));
+
+    // every output before the diagnostic code is a passed test
+    let (code, tests) = outputs.split_last().expect("No diagnostic code");
+    assert!(tests.iter().all(|it| *it == 0), "Failed tests: {:?}", tests);
+    *code
+}
+
+pub fn run_diagnostic<I, O>(input: &str, mut next_input: I, mut on_output: O) -> Result<(), String>
+where
+    I: FnMut() -> Result<i64, String>,
+    O: FnMut(i64),
+{
+    check_intcode_input(input)?;
+    let mut program = IntcodeProgram::init_from(input);
+    program.set_instruction_set(InstructionSet::Day5);
+
+    while !program.is_halted() {
+        program.try_run().map_err(|e| e.to_string())?;
+
+        while let Some(output) = program.consume_output() {
+            on_output(output);
+        }
+
+        if program.is_awaiting_input() {
+            program.buffer_input(next_input()?);
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,104 @@
+extern crate intcode;
+extern crate log;
+extern crate permutate;
+
+use answers::Answers;
+use intcode::network::{Network, Route, Stop};
+use intcode::{check_intcode_input, parse_intcode_input, IntcodeProgram};
+use log::debug;
+use permutate::Permutator;
+use std::cmp::max;
+
+pub const INPUT: &str = include_str!("../day7.txt");
+const INPUT_SIGNAL: i64 = 0;
+const PT1_PHASES: [&i64; 5] = [&0, &1, &2, &3, &4];
+const PT2_PHASES: [&i64; 5] = [&9, &8, &7, &6, &5];
+
+pub fn solve(input: &str) -> Result<Answers<'_>, String> {
+    check_intcode_input(input)?;
+    Ok(Answers::new(
+        move || {
+            Ok(maximum_signal(
+                parse_intcode_input(input),
+                false,
+                PT1_PHASES,
+            ))
+        },
+        move || Ok(maximum_signal(parse_intcode_input(input), true, PT2_PHASES)),
+    ))
+}
+
+fn maximum_signal(
+    original_memory: Vec<i64>,
+    with_feedback: bool,
+    possible_phases: [&i64; 5],
+) -> i64 {
+    // TODO: Well, definitely write something better for permuting w/o repetition...
+    let da_phases = possible_phases;
+    let possible_phases: &[&i64] = &possible_phases;
+    let possible_phases = [possible_phases];
+    let mut permutator = Permutator::new(&possible_phases[..]);
+
+    let mut max_signal: Option<i64> = None;
+    while let Some(permutation) = next_non_repeating(&mut permutator, &da_phases) {
+        let signal = run_all_amplifiers(&mut original_memory.clone(), &permutation, with_feedback);
+        max_signal = match max_signal {
+            Some(m_s) => Some(max(m_s, signal)),
+            None => Some(signal),
+        };
+    }
+    return max_signal.unwrap();
+}
+
+fn run_all_amplifiers(start_memory: &Vec<i64>, phases: &Vec<&i64>, with_feedback: bool) -> i64 {
+    let intcode_amps: Vec<IntcodeProgram> = phases
+        .iter()
+        .map(|phase| IntcodeProgram::init(start_memory, vec![**phase]))
+        .collect();
+
+    let mut network = Network::new(intcode_amps);
+    network.send(0, &[INPUT_SIGNAL]).expect("Amplifier failed");
+
+    let last_amp_i = phases.len() - 1;
+    let mut last_output = None;
+    let stop = network
+        .run(|amp_i, output| {
+            let next_amp_i = (amp_i + 1) % phases.len();
+            debug!("Amp {}: {} -> Amp {}", amp_i, output[0], next_amp_i);
+
+            if amp_i == last_amp_i {
+                last_output = Some(output[0]);
+                if !with_feedback {
+                    return Route::Drop;
+                }
+            }
+            Route::To(next_amp_i, output.to_vec())
+        })
+        .expect("Amplifier failed");
+
+    match stop {
+        Stop::AllHalted => last_output.expect("No output from amplifiers"),
+        stop => panic!("Amplifiers stopped before halting: {:?}", stop),
+    }
+}
+
+fn next_non_repeating<'a>(
+    permutator: &mut Permutator<'a, i64>,
+    phases: &[&i64; 5],
+) -> Option<Vec<&'a i64>> {
+    fn contains_all_phases(permutation: &Vec<&i64>, phases: &[&i64; 5]) -> bool {
+        for phase in phases {
+            if !permutation.contains(phase) {
+                return false;
+            }
+        }
+        true
+    }
+
+    while let Some(permutation) = permutator.next() {
+        if contains_all_phases(&permutation, phases) {
+            return Some(permutation);
+        }
+    }
+    None
+}
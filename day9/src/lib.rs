@@ -0,0 +1,26 @@
+extern crate env_logger;
+extern crate intcode;
+extern crate log;
+
+use answers::Answers;
+use intcode::{check_intcode_input, IntcodeProgram};
+
+pub const INPUT: &str = include_str!("../day9.txt");
+
+pub fn solve(input: &str) -> Result<Answers<'_>, String> {
+    check_intcode_input(input)?;
+    Ok(Answers::new(
+        move || Ok(run_day9(input, 1)),
+        move || Ok(run_day9(input, 2)),
+    ))
+}
+
+fn run_day9(input: &str, input_code: i64) -> i64 {
+    let mut program = IntcodeProgram::init_from(input);
+    let mut input = Some(input_code);
+    program
+        .outputs(|| input.take())
+        .next()
+        .expect("No output")
+        .expect("BOOST program failed")
+}
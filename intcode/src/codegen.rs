@@ -0,0 +1,375 @@
+//! Ahead-of-time translation of intcode programs to Rust source.
+//!
+//! The instructions reachable from address 0 are decoded and split into basic blocks, and each
+//! block becomes an arm of a `match pc` in a generated `step` function. Operands are inlined as
+//! constants, so a block is only valid until the program changes one of its instructions. A store
+//! to a translated block, or a jump to an address that isn't the start of one, hands the program
+//! over to the interpreter until it reaches an unmodified block (see [`crate::compiled`]). So does
+//! an instruction that would fail, e.g. by overflowing or addressing memory out of bounds: it's
+//! left undone, and the interpreter runs it again to report the error.
+//!
+//! The output is meant to be written to a file by a build script and `include!`d into a module,
+//! which then provides `program()` to create a [`crate::compiled::CompiledProgram`], and `IMAGE`,
+//! the program it was translated from:
+//!
+//! ```ignore
+//! // build.rs
+//! let source = intcode::codegen::generate_rust(&parse_intcode_input(PROGRAM));
+//! std::fs::write(Path::new(&env::var("OUT_DIR").unwrap()).join("program.rs"), source).unwrap();
+//!
+//! // main.rs
+//! mod program {
+//!     include!(concat!(env!("OUT_DIR"), "/program.rs"));
+//! }
+//! ```
+
+use crate::extension::OpcodeRegistry;
+use crate::{destructure_inst, AddressingMode, Instruction, Op};
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt::Write;
+
+/// The decoded instructions of a program, with the addresses that start a basic block.
+struct Analysis {
+    instructions: BTreeMap<usize, Instruction>,
+    leaders: BTreeSet<usize>,
+}
+
+/// Decodes every instruction reachable from address 0, following both sides of every branch.
+/// Targets of indirect jumps can't be known, so values that look like code addresses (such as
+/// return addresses pushed by a call) are treated as block starts too.
+fn analyze(memory: &[i64]) -> Analysis {
+    let opcodes = OpcodeRegistry::new();
+    let mut instructions = BTreeMap::new();
+    let mut leaders = BTreeSet::new();
+    let mut immediates = BTreeSet::new();
+
+    let mut to_visit = vec![0];
+    leaders.insert(0);
+    while let Some(pc) = to_visit.pop() {
+        if pc >= memory.len() || instructions.contains_key(&pc) {
+            continue;
+        }
+        let inst = match destructure_inst(memory[pc], &opcodes) {
+            Ok(inst) => inst,
+            // left to the interpreter to report
+            Err(_) => continue,
+        };
+        let len = inst.op.num_parameters() + 1;
+        if pc + len > memory.len() {
+            continue;
+        }
+        let param = |i: usize| memory[pc + i + 1];
+
+        for (i, mode) in inst.addr_modes.iter().enumerate() {
+            if *mode == AddressingMode::Immediate && param(i) >= 0 {
+                immediates.insert(param(i) as usize);
+            }
+        }
+
+        match inst.op {
+            Op::Jit | Op::Jif => {
+                if inst.addr_modes[1] == AddressingMode::Immediate && param(1) >= 0 {
+                    leaders.insert(param(1) as usize);
+                    to_visit.push(param(1) as usize);
+                }
+                leaders.insert(pc + len);
+                to_visit.push(pc + len);
+            }
+            Op::Halt => {}
+            Op::Input => {
+                // resuming after waiting for input starts from the input instruction
+                leaders.insert(pc);
+                to_visit.push(pc + len);
+            }
+            _ => to_visit.push(pc + len),
+        }
+        instructions.insert(pc, inst);
+    }
+
+    for addr in immediates {
+        if instructions.contains_key(&addr) {
+            leaders.insert(addr);
+        }
+    }
+    Analysis {
+        instructions,
+        leaders,
+    }
+}
+
+/// Translates `memory` into Rust source for a module with a `program()` function. See the module
+/// documentation for how to use it.
+pub fn generate_rust(memory: &[i64]) -> String {
+    let analysis = analyze(memory);
+
+    let mut blocks = Vec::new();
+    let mut arms = String::new();
+    for leader in analysis.leaders.iter() {
+        if analysis.instructions.contains_key(leader) {
+            let end = write_block(&mut arms, memory, &analysis, *leader);
+            blocks.push((*leader, end));
+        }
+    }
+
+    let mut out = String::new();
+    writeln!(out, "// Generated by intcode::codegen. Do not edit.").unwrap();
+    writeln!(out).unwrap();
+    writeln!(out, "pub const IMAGE: &[i64] = &{:?};", memory).unwrap();
+    writeln!(out).unwrap();
+    writeln!(out, "const BLOCKS: &[(usize, usize)] = &{:?};", blocks).unwrap();
+    writeln!(out).unwrap();
+    writeln!(
+        out,
+        "pub fn program() -> ::intcode::compiled::CompiledProgram {{"
+    )
+    .unwrap();
+    writeln!(
+        out,
+        "    ::intcode::compiled::CompiledProgram::new(IMAGE, BLOCKS, step)"
+    )
+    .unwrap();
+    writeln!(out, "}}").unwrap();
+    writeln!(out).unwrap();
+
+    writeln!(
+        out,
+        "#[allow(clippy::all, unreachable_code, unused_mut, unused_parens)]"
+    )
+    .unwrap();
+    writeln!(
+        out,
+        "fn step(m: &mut ::intcode::compiled::Machine) -> ::intcode::compiled::Exit {{"
+    )
+    .unwrap();
+    writeln!(out, "    use ::intcode::compiled::Exit;").unwrap();
+    writeln!(out, "    loop {{").unwrap();
+    writeln!(out, "        if m.is_modified(m.pc) {{").unwrap();
+    writeln!(out, "            return Exit::Fallback;").unwrap();
+    writeln!(out, "        }}").unwrap();
+    writeln!(out, "        match m.pc {{").unwrap();
+    out.push_str(&arms);
+    writeln!(out, "            _ => return Exit::Fallback,").unwrap();
+    writeln!(out, "        }}").unwrap();
+    writeln!(out, "    }}").unwrap();
+    writeln!(out, "}}").unwrap();
+    out
+}
+
+/// Writes the match arm for the block starting at `leader`, returning the address after its last
+/// instruction.
+fn write_block(out: &mut String, memory: &[i64], analysis: &Analysis, leader: usize) -> usize {
+    writeln!(out, "            {} => {{", leader).unwrap();
+
+    let mut pc = leader;
+    let end = loop {
+        let inst = &analysis.instructions[&pc];
+        let next = pc + inst.op.num_parameters() + 1;
+        writeln!(out, "                // {}: {}", pc, inst.op.name()).unwrap();
+        for line in translate(memory, pc, inst) {
+            writeln!(out, "                {}", line).unwrap();
+        }
+
+        let ends_block = matches!(inst.op, Op::Jit | Op::Jif | Op::Halt);
+        if ends_block
+            || analysis.leaders.contains(&next)
+            || !analysis.instructions.contains_key(&next)
+        {
+            // jumps set the PC themselves
+            if !matches!(inst.op, Op::Jit | Op::Jif | Op::Halt) {
+                writeln!(out, "                m.pc = {};", next).unwrap();
+            }
+            break next;
+        }
+        pc = next;
+    };
+    writeln!(out, "            }}").unwrap();
+    end
+}
+
+/// Rust statements for the instruction at `pc`. Everything that can fail is checked before the
+/// instruction has any effect, and hands over to the interpreter if it does.
+fn translate(memory: &[i64], pc: usize, inst: &Instruction) -> Vec<String> {
+    let next = pc + inst.op.num_parameters() + 1;
+    let param = |i: usize| memory[pc + i + 1];
+    let fault = format!("{{ m.pc = {}; return Exit::Fallback; }}", pc);
+    let addr = |i: usize| match inst.addr_modes[i] {
+        AddressingMode::Position => format!("{}", param(i)),
+        AddressingMode::Immediate => format!("{}", pc + i + 1),
+        AddressingMode::Relative => format!("m.rb.saturating_add({})", param(i)),
+    };
+
+    // values read from memory are loaded first, so that a bad address is caught early
+    let num_reads = match inst.op {
+        Op::Add | Op::Mul | Op::Lt | Op::Eq | Op::Jit | Op::Jif => 2,
+        Op::Output | Op::RelBaseOffset => 1,
+        _ => 0,
+    };
+    let mut lines = Vec::new();
+    let reads: Vec<String> = (0..num_reads)
+        .map(|i| match inst.addr_modes[i] {
+            AddressingMode::Immediate => literal(param(i)),
+            _ => {
+                lines.push(format!(
+                    "let Some(p{}) = m.load({}) else {};",
+                    i,
+                    addr(i),
+                    fault
+                ));
+                format!("p{}", i)
+            }
+        })
+        .collect();
+    let check_addr =
+        |i: usize| format!("let Some(a{}) = m.address({}) else {};", i, addr(i), fault);
+    let store = |i: usize| {
+        format!(
+            "if m.store(a{}, value) {{ m.pc = {}; return Exit::Fallback; }}",
+            i, next
+        )
+    };
+    let checked = |method: &str| {
+        format!(
+            "let Some(value) = {}.{}({}) else {};",
+            reads[0], method, reads[1], fault
+        )
+    };
+
+    match inst.op {
+        Op::Add | Op::Mul => {
+            lines.push(check_addr(2));
+            lines.push(checked(if inst.op == Op::Add {
+                "checked_add"
+            } else {
+                "checked_mul"
+            }));
+            lines.push(store(2));
+        }
+        Op::Lt | Op::Eq => {
+            lines.push(check_addr(2));
+            lines.push(format!(
+                "let value = ({} {} {}) as i64;",
+                reads[0],
+                if inst.op == Op::Lt { "<" } else { "==" },
+                reads[1]
+            ));
+            lines.push(store(2));
+        }
+        Op::Input => {
+            lines.push(check_addr(0));
+            lines.push(format!(
+                "let value = match m.input() {{ Some(value) => value, None => {{ m.pc = {}; return Exit::AwaitingInput; }} }};",
+                pc
+            ));
+            lines.push(store(0));
+        }
+        Op::Output => lines.push(format!("m.output({});", reads[0])),
+        Op::Jit | Op::Jif => {
+            lines.push(format!(
+                "if {} {} 0 {{ if !m.jump({}) {} }} else {{ m.pc = {}; }}",
+                reads[0],
+                if inst.op == Op::Jit { "!=" } else { "==" },
+                reads[1],
+                fault,
+                next
+            ));
+            lines.push("if m.pauses_at_branches() { return Exit::Branch; }".to_string());
+        }
+        Op::RelBaseOffset => {
+            lines.push(format!(
+                "let Some(rb) = m.rb.checked_add({}) else {};",
+                reads[0], fault
+            ));
+            lines.push("m.rb = rb;".to_string());
+        }
+        Op::Halt => {
+            lines.push(format!("m.pc = {};", pc));
+            lines.push("return Exit::Halted;".to_string());
+        }
+        Op::Custom(..) => unreachable!("custom opcodes aren't decoded without a registry"),
+    }
+    lines
+}
+
+/// An immediate value as a Rust expression that methods can be called on.
+fn literal(value: i64) -> String {
+    if value == i64::MIN {
+        "i64::MIN".to_string()
+    } else if value < 0 {
+        format!("({}i64)", value)
+    } else {
+        format!("{}i64", value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::codegen::{analyze, generate_rust};
+    use crate::parse_intcode_input;
+
+    #[test]
+    fn test_blocks() {
+        // is the input equal to 8?
+        let memory = parse_intcode_input("3,9,8,9,10,9,4,9,99,-1,8");
+        let analysis = analyze(&memory);
+        assert_eq!(
+            analysis
+                .instructions
+                .keys()
+                .cloned()
+                .collect::<Vec<usize>>(),
+            vec![0, 2, 6, 8]
+        );
+        assert_eq!(
+            analysis.leaders.iter().cloned().collect::<Vec<usize>>(),
+            vec![0]
+        );
+
+        // jump targets and the instructions after jumps start blocks
+        let memory = parse_intcode_input("3,12,1006,12,9,1,13,14,13,4,13,99,-1,0,1");
+        let analysis = analyze(&memory);
+        assert_eq!(
+            analysis.leaders.iter().cloned().collect::<Vec<usize>>(),
+            vec![0, 5, 9]
+        );
+    }
+
+    #[test]
+    fn test_indirect_jump_targets() {
+        // stores the address of the output, then loops back to it with an indirect jump
+        let memory = parse_intcode_input(
+            "1101,0,4,100,104,1,1001,101,1,101,1008,101,3,102,1005,102,20,6,103,100,99",
+        );
+        let analysis = analyze(&memory);
+        assert_eq!(
+            analysis.leaders.iter().cloned().collect::<Vec<usize>>(),
+            vec![0, 4, 17, 20]
+        );
+    }
+
+    #[test]
+    fn test_generated_source() {
+        let source = generate_rust(&parse_intcode_input("109,5,204,-3,1101,2,3,1,99"));
+        assert!(source.contains("const IMAGE: &[i64] = &[109, 5, 204, -3, 1101, 2, 3, 1, 99];"));
+        assert!(source.contains("const BLOCKS: &[(usize, usize)] = &[(0, 2), (2, 9)];"));
+        assert!(source.contains(
+            "let Some(rb) = m.rb.checked_add(5i64) else { m.pc = 0; return Exit::Fallback; };"
+        ));
+        assert!(source.contains(
+            "let Some(p0) = m.load(m.rb.saturating_add(-3)) else { m.pc = 2; return Exit::Fallback; };"
+        ));
+        assert!(source.contains("m.output(p0);"));
+        assert!(source.contains(
+            "let Some(value) = 2i64.checked_add(3i64) else { m.pc = 4; return Exit::Fallback; };"
+        ));
+        // the store overwrites the add's own operand
+        assert!(source.contains("if m.store(a2, value) { m.pc = 8; return Exit::Fallback; }"));
+        assert!(source.contains("return Exit::Halted;"));
+
+        // the jump sets the PC on both sides, and can pause afterwards
+        let source = generate_rust(&parse_intcode_input("1006,6,5,104,1,99,0"));
+        assert!(source.contains(
+            "if p0 == 0 { if !m.jump(5i64) { m.pc = 0; return Exit::Fallback; } } else { m.pc = 3; }"
+        ));
+        assert!(source.contains("if m.pauses_at_branches() { return Exit::Branch; }"));
+    }
+}
@@ -0,0 +1,457 @@
+//! Runtime support for programs translated to Rust by [`crate::codegen`].
+//!
+//! Generated code runs against a [`Machine`], and a [`CompiledProgram`] wraps it with the same
+//! interface as `IntcodeProgram`. Whenever the compiled code can't continue, because the program
+//! wrote to the block it's about to run or jumped somewhere that wasn't translated, the interpreter
+//! takes over from the exact same state until the program is back in a block that's still valid.
+//! Stores made by the interpreter invalidate the blocks they write to, just like compiled ones.
+
+use crate::machine::IntcodeMachine;
+use crate::observer::Observer;
+use crate::{IntcodeError, IntcodeProgram, IntcodeResult};
+use std::cell::RefCell;
+use std::rc::Rc;
+
+/// Why generated code returned.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Exit {
+    Halted,
+    AwaitingInput,
+    /// The interpreter has to take over at the machine's current PC, e.g. to run an instruction
+    /// that would fail and report the error.
+    Fallback,
+    /// A jump instruction ran while the machine was pausing at branches.
+    Branch,
+}
+
+pub type StepFn = fn(&mut Machine) -> Exit;
+
+/// The state that generated code operates on. Only meant to be used by generated code.
+pub struct Machine {
+    pub pc: usize,
+    pub rb: i64,
+    /// Holds the memory and I/O, and runs whatever the generated code can't.
+    program: IntcodeProgram,
+    /// The start of the translated block each address belongs to.
+    block_of: Vec<Option<usize>>,
+    /// Whether the block starting at each address has been written to.
+    modified: Vec<bool>,
+    /// The addresses the interpreter has stored to, which have to be checked for blocks too.
+    interpreter_stores: Rc<RefCell<StoreLog>>,
+    /// Whether to return after every jump instruction, see `IntcodeMachine::try_run_to_branch`.
+    pause_at_branches: bool,
+}
+
+/// Records every store the interpreter makes.
+#[derive(Default)]
+struct StoreLog {
+    addrs: Vec<usize>,
+}
+
+impl Observer for StoreLog {
+    fn on_write(&mut self, addr: usize, _value: i64) {
+        self.addrs.push(addr);
+    }
+}
+
+impl Machine {
+    /// The value at `addr`, or `None` if it's outside of memory.
+    #[inline]
+    pub fn load(&self, addr: i64) -> Option<i64> {
+        self.address(addr).map(|addr| self.program.memory.get(addr))
+    }
+
+    /// `addr` as an index into memory, or `None` if it's outside of memory.
+    #[inline]
+    pub fn address(&self, addr: i64) -> Option<usize> {
+        if addr < 0 || !self.program.memory.contains(addr as usize) {
+            return None;
+        }
+        Some(addr as usize)
+    }
+
+    /// Stores `value` at an address from `address`, returning true if it overwrote part of a
+    /// translated block.
+    #[inline]
+    pub fn store(&mut self, addr: usize, value: i64) -> bool {
+        self.program.memory.set(addr, value);
+        self.invalidate(addr)
+    }
+
+    /// Marks the block `addr` belongs to as modified, returning false if it isn't in one.
+    fn invalidate(&mut self, addr: usize) -> bool {
+        match self.block_of.get(addr) {
+            Some(Some(start)) => {
+                self.modified[*start] = true;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Continues at `target`, returning false without jumping if it's outside of memory.
+    #[inline]
+    pub fn jump(&mut self, target: i64) -> bool {
+        match self.address(target) {
+            Some(target) => {
+                self.pc = target;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Whether generated code should return `Exit::Branch` after a jump instruction.
+    #[inline]
+    pub fn pauses_at_branches(&self) -> bool {
+        self.pause_at_branches
+    }
+
+    /// Whether the block starting at `pc` has been written to since it was translated.
+    #[inline]
+    pub fn is_modified(&self, pc: usize) -> bool {
+        self.modified.get(pc).copied().unwrap_or(false)
+    }
+
+    #[inline]
+    pub fn input(&mut self) -> Option<i64> {
+        let input = self.program.consume_input();
+        self.program.is_awaiting_input = input.is_none();
+        input
+    }
+
+    #[inline]
+    pub fn output(&mut self, value: i64) {
+        self.program.buffer_output(value);
+    }
+
+    fn is_valid_block(&self, pc: usize) -> bool {
+        self.block_of.get(pc) == Some(&Some(pc)) && !self.modified[pc]
+    }
+}
+
+/// A program translated to Rust, which falls back to the interpreter when it has to.
+pub struct CompiledProgram {
+    step: StepFn,
+    machine: Machine,
+    interpreted: usize,
+}
+
+impl CompiledProgram {
+    /// Used by generated code: `image` is the translated program, `blocks` lists the (start, end)
+    /// addresses of every translated block and `step` is the generated code.
+    pub fn new(image: &[i64], blocks: &[(usize, usize)], step: StepFn) -> CompiledProgram {
+        let mut block_of = vec![None; image.len()];
+        for (start, end) in blocks.iter() {
+            block_of[*start..*end]
+                .iter_mut()
+                .for_each(|it| *it = Some(*start));
+        }
+
+        let interpreter_stores = Rc::new(RefCell::new(StoreLog::default()));
+        let mut program = IntcodeProgram::init(&image.to_vec(), Vec::new());
+        program.add_observer(interpreter_stores.clone());
+
+        CompiledProgram {
+            step,
+            machine: Machine {
+                pc: 0,
+                rb: 0,
+                program,
+                block_of,
+                modified: vec![false; image.len()],
+                interpreter_stores,
+                pause_at_branches: false,
+            },
+            interpreted: 0,
+        }
+    }
+
+    /// Changes memory before running, e.g. to patch the program. A patched block runs in the
+    /// interpreter instead.
+    pub fn set_mem_value(&mut self, addr: usize, value: i64) {
+        self.machine.store(addr, value);
+    }
+
+    /// How many instructions had to run in the interpreter rather than as compiled code.
+    pub fn interpreted_instructions(&self) -> usize {
+        self.interpreted
+    }
+
+    pub fn run(&mut self) {
+        if let Err(e) = self.try_run() {
+            panic!("Aborting, {}", e)
+        }
+    }
+
+    pub fn try_run(&mut self) -> Result<(), IntcodeError> {
+        while !self.is_halted() {
+            match (self.step)(&mut self.machine) {
+                Exit::Halted => {
+                    self.machine.program.pc = self.machine.pc;
+                    self.machine.program.relative_base = self.machine.rb;
+                    self.machine.program.halt();
+                }
+                Exit::AwaitingInput | Exit::Branch => return Ok(()),
+                Exit::Fallback => {
+                    if !self.interpret()? {
+                        return Ok(());
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Like `try_run`, but returns after every jump instruction too.
+    pub fn try_run_to_branch(&mut self) -> Result<(), IntcodeError> {
+        self.machine.pause_at_branches = true;
+        let result = self.try_run();
+        self.machine.pause_at_branches = false;
+        result
+    }
+
+    /// Runs the interpreter until the PC is at the start of a valid block again. Returns false if
+    /// the program stopped to wait for input, halted or paused at a branch first.
+    fn interpret(&mut self) -> Result<bool, IntcodeError> {
+        let machine = &mut self.machine;
+        machine.program.pc = machine.pc;
+        machine.program.relative_base = machine.rb;
+
+        let result = loop {
+            let is_branch = machine.pause_at_branches && machine.program.is_at_branch();
+            let step = machine.program.step();
+            if step.is_ok() {
+                self.interpreted += 1;
+            }
+            let stores = std::mem::take(&mut machine.interpreter_stores.borrow_mut().addrs);
+            for addr in stores {
+                machine.invalidate(addr);
+            }
+            match step {
+                Ok(IntcodeResult::ExecutedInstruction) => {
+                    if is_branch {
+                        break Ok(false);
+                    }
+                    if machine.is_valid_block(machine.program.pc) {
+                        break Ok(true);
+                    }
+                }
+                Ok(IntcodeResult::AwaitingInput) | Ok(IntcodeResult::Halted) => break Ok(false),
+                Err(e) => break Err(e),
+            }
+        };
+
+        machine.pc = machine.program.pc;
+        machine.rb = machine.program.relative_base;
+        result
+    }
+
+    pub fn mem_value(&self, addr: usize) -> i64 {
+        self.machine.program.mem_value(addr)
+    }
+
+    pub fn buffer_input(&mut self, input: i64) {
+        self.machine.program.buffer_input(input)
+    }
+
+    pub fn consume_output(&mut self) -> Option<i64> {
+        self.machine.program.consume_output()
+    }
+
+    pub fn is_awaiting_input(&self) -> bool {
+        self.machine.program.is_awaiting_input()
+    }
+
+    pub fn is_halted(&self) -> bool {
+        self.machine.program.is_halted()
+    }
+}
+
+impl IntcodeMachine for CompiledProgram {
+    fn try_run(&mut self) -> Result<(), IntcodeError> {
+        CompiledProgram::try_run(self)
+    }
+
+    fn try_run_to_branch(&mut self) -> Result<(), IntcodeError> {
+        CompiledProgram::try_run_to_branch(self)
+    }
+
+    fn buffer_input(&mut self, input: i64) {
+        CompiledProgram::buffer_input(self, input)
+    }
+
+    fn consume_output(&mut self) -> Option<i64> {
+        CompiledProgram::consume_output(self)
+    }
+
+    fn mem_value(&self, addr: usize) -> i64 {
+        CompiledProgram::mem_value(self, addr)
+    }
+
+    fn memory_hash(&self) -> u64 {
+        self.machine.program.memory.hash()
+    }
+
+    fn pc(&self) -> usize {
+        self.machine.pc
+    }
+
+    fn is_awaiting_input(&self) -> bool {
+        CompiledProgram::is_awaiting_input(self)
+    }
+
+    fn is_halted(&self) -> bool {
+        CompiledProgram::is_halted(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::codegen::generate_rust;
+    use crate::compiled::{CompiledProgram, Exit, Machine};
+    use crate::{parse_intcode_input, IntcodeProgram};
+
+    // What `generate_rust` produces for "1002,4,3,4,33", which turns its last parameter into a
+    // halt instruction.
+    const IMAGE: &[i64] = &[1002, 4, 3, 4, 33];
+    const BLOCKS: &[(usize, usize)] = &[(0, 4)];
+
+    fn step(m: &mut Machine) -> Exit {
+        loop {
+            if m.is_modified(m.pc) {
+                return Exit::Fallback;
+            }
+            match m.pc {
+                0 => {
+                    // 0: MUL
+                    let Some(p0) = m.load(4) else {
+                        m.pc = 0;
+                        return Exit::Fallback;
+                    };
+                    let Some(a2) = m.address(4) else {
+                        m.pc = 0;
+                        return Exit::Fallback;
+                    };
+                    let Some(value) = p0.checked_mul(3i64) else {
+                        m.pc = 0;
+                        return Exit::Fallback;
+                    };
+                    if m.store(a2, value) {
+                        m.pc = 4;
+                        return Exit::Fallback;
+                    }
+                    m.pc = 4;
+                }
+                _ => return Exit::Fallback,
+            }
+        }
+    }
+
+    // What `generate_rust` produces for the block at 7 in "1101,5,0,8,1105,1,7,104,0,99", whose
+    // output is changed by the first instruction. The first block is left out, since the test
+    // patches it so that it's interpreted.
+    #[allow(clippy::never_loop)]
+    fn output_step(m: &mut Machine) -> Exit {
+        loop {
+            if m.is_modified(m.pc) {
+                return Exit::Fallback;
+            }
+            match m.pc {
+                7 => {
+                    // 7: OUT
+                    m.output(0i64);
+                    // 9: HALT
+                    m.pc = 9;
+                    return Exit::Halted;
+                }
+                _ => return Exit::Fallback,
+            }
+        }
+    }
+
+    // What `generate_rust` produces for "109,5,99".
+    #[allow(clippy::never_loop)]
+    fn relative_base_step(m: &mut Machine) -> Exit {
+        loop {
+            if m.is_modified(m.pc) {
+                return Exit::Fallback;
+            }
+            match m.pc {
+                0 => {
+                    // 0: ARB
+                    let Some(rb) = m.rb.checked_add(5i64) else {
+                        m.pc = 0;
+                        return Exit::Fallback;
+                    };
+                    m.rb = rb;
+                    // 2: HALT
+                    m.pc = 2;
+                    return Exit::Halted;
+                }
+                _ => return Exit::Fallback,
+            }
+        }
+    }
+
+    #[test]
+    fn test_generated_step() {
+        let source = generate_rust(&parse_intcode_input("1002,4,3,4,33"));
+        assert!(source.contains("const IMAGE: &[i64] = &[1002, 4, 3, 4, 33];"));
+        assert!(source.contains("const BLOCKS: &[(usize, usize)] = &[(0, 4)];"));
+        assert!(source.contains(
+            "let Some(value) = p0.checked_mul(3i64) else { m.pc = 0; return Exit::Fallback; };"
+        ));
+        assert!(source.contains("if m.store(a2, value) { m.pc = 4; return Exit::Fallback; }"));
+    }
+
+    #[test]
+    fn test_untranslated_code_is_interpreted() {
+        // the halt instruction at 4 only exists after the multiplication
+        let mut program = CompiledProgram::new(IMAGE, BLOCKS, step);
+        program.run();
+        assert!(program.is_halted());
+        assert_eq!(program.mem_value(4), 99);
+        assert_eq!(program.interpreted_instructions(), 1);
+    }
+
+    #[test]
+    fn test_patched_block_is_interpreted() {
+        let mut program = CompiledProgram::new(IMAGE, BLOCKS, step);
+        program.set_mem_value(10, 1);
+        assert_eq!(program.mem_value(10), 1);
+
+        // multiplies by 4 instead, which makes 132: an invalid opcode
+        program.set_mem_value(2, 4);
+        assert_eq!(
+            program.try_run().unwrap_err().to_string(),
+            "PC 4: Invalid opcode: 32"
+        );
+        assert_eq!(program.interpreted_instructions(), 1);
+    }
+
+    #[test]
+    fn test_interpreted_store_invalidates_block() {
+        let image = parse_intcode_input("1101,5,0,8,1105,1,7,104,0,99");
+        let mut program = CompiledProgram::new(&image, &[(0, 7), (7, 10)], output_step);
+        program.set_mem_value(1, 5);
+        program.run();
+        assert_eq!(program.consume_output(), Some(5));
+        assert_eq!(program.interpreted_instructions(), 4);
+    }
+
+    #[test]
+    fn test_halted_state_matches_interpreter() {
+        let image = parse_intcode_input("109,5,99");
+        let mut compiled = CompiledProgram::new(&image, &[(0, 3)], relative_base_step);
+        compiled.run();
+        let mut interpreted = IntcodeProgram::init(&image, Vec::new());
+        interpreted.run();
+
+        assert_eq!(compiled.interpreted_instructions(), 0);
+        assert_eq!(
+            compiled.machine.program.fingerprint(),
+            interpreted.fingerprint()
+        );
+    }
+}
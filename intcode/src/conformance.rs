@@ -0,0 +1,170 @@
+//! Runs the corpus of example programs in `conformance/`. See the README there for the format.
+
+use crate::{parse_intcode_input, IntcodeProgram};
+use std::fs;
+use std::path::Path;
+
+#[derive(Debug, PartialEq)]
+enum End {
+    Halted,
+    Waiting,
+    Error(String),
+}
+
+#[derive(Debug)]
+struct Case {
+    name: String,
+    program: Vec<i64>,
+    inputs: Vec<i64>,
+    outputs: Vec<i64>,
+    memory: Vec<i64>,
+    mem: Vec<(usize, i64)>,
+    end: End,
+}
+
+fn parse_values(values: &str) -> Vec<i64> {
+    values
+        .split(',')
+        .map(|value| value.trim().parse().unwrap())
+        .collect()
+}
+
+fn parse_cases(text: &str) -> Result<Vec<Case>, String> {
+    let mut cases: Vec<Case> = Vec::new();
+
+    for (i, line) in text.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let (keyword, rest) = match line.find(' ') {
+            Some(space) => (&line[..space], line[space + 1..].trim()),
+            None => (line, ""),
+        };
+
+        if keyword == "case" {
+            cases.push(Case {
+                name: rest.to_string(),
+                program: Vec::new(),
+                inputs: Vec::new(),
+                outputs: Vec::new(),
+                memory: Vec::new(),
+                mem: Vec::new(),
+                end: End::Halted,
+            });
+            continue;
+        }
+        let case = match cases.last_mut() {
+            Some(case) => case,
+            None => return Err(format!("line {}: expected a case first", i + 1)),
+        };
+        match keyword {
+            "program" => case.program = parse_intcode_input(rest),
+            "in" => case.inputs.extend(parse_values(rest)),
+            "out" => case.outputs.extend(parse_values(rest)),
+            "memory" => case.memory = parse_values(rest),
+            "mem" => {
+                for pair in rest.split(',') {
+                    let mut parts = pair.split('=');
+                    let addr = parts.next().unwrap().trim().parse().unwrap();
+                    let value = parts.next().unwrap().trim().parse().unwrap();
+                    case.mem.push((addr, value));
+                }
+            }
+            "error" => case.end = End::Error(rest.to_string()),
+            "waiting" => case.end = End::Waiting,
+            _ => return Err(format!("line {}: unknown keyword {}", i + 1, keyword)),
+        }
+    }
+    Ok(cases)
+}
+
+/// Runs `case`, returning what's different from what was expected.
+fn check(case: &Case) -> Result<(), String> {
+    let mut program = IntcodeProgram::init(&case.program, case.inputs.clone());
+    let end = match program.try_run() {
+        Err(e) => End::Error(e.to_string()),
+        Ok(()) if program.is_halted() => End::Halted,
+        Ok(()) => End::Waiting,
+    };
+    if end != case.end {
+        return Err(format!("expected to end {:?}, ended {:?}", case.end, end));
+    }
+
+    let outputs: Vec<i64> = std::iter::from_fn(|| program.consume_output()).collect();
+    if outputs != case.outputs {
+        return Err(format!(
+            "expected outputs {:?}, got {:?}",
+            case.outputs, outputs
+        ));
+    }
+
+    let expected_memory = case.memory.iter().cloned().enumerate();
+    for (addr, expected) in expected_memory.chain(case.mem.iter().cloned()) {
+        let value = program.mem_value(addr);
+        if value != expected {
+            return Err(format!("expected {} at {}, got {}", expected, addr, value));
+        }
+    }
+    Ok(())
+}
+
+#[test]
+fn test_conformance_corpus() {
+    let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("conformance");
+    let mut files: Vec<_> = fs::read_dir(&dir)
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "txt"))
+        .collect();
+    files.sort();
+    assert!(!files.is_empty(), "No cases in {:?}", dir);
+
+    let mut failures = Vec::new();
+    for file in files {
+        let name = file.file_name().unwrap().to_string_lossy().to_string();
+        let cases = parse_cases(&fs::read_to_string(&file).unwrap())
+            .unwrap_or_else(|e| panic!("{}: {}", name, e));
+        assert!(!cases.is_empty(), "No cases in {}", name);
+
+        for case in cases {
+            if let Err(e) = check(&case) {
+                failures.push(format!("{}: {}: {}", name, case.name, e));
+            }
+        }
+    }
+    assert!(failures.is_empty(), "\n{}", failures.join("\n"));
+}
+
+#[test]
+fn test_parse_cases() {
+    let cases = parse_cases(
+        "# comment\n\
+         case one\n\
+         program 3,0,99\n\
+         in 1, 2\n\
+         in 3\n\
+         mem 0=1,5=0\n\
+         waiting\n\
+         \n\
+         case two\n\
+         program 99\n\
+         error PC 0: oops\n",
+    )
+    .unwrap();
+    assert_eq!(cases.len(), 2);
+    assert_eq!(cases[0].name, "one");
+    assert_eq!(cases[0].inputs, vec![1, 2, 3]);
+    assert_eq!(cases[0].mem, vec![(0, 1), (5, 0)]);
+    assert_eq!(cases[0].end, End::Waiting);
+    assert_eq!(cases[1].end, End::Error("PC 0: oops".to_string()));
+
+    assert_eq!(
+        parse_cases("program 99").unwrap_err(),
+        "line 1: expected a case first"
+    );
+    assert_eq!(
+        parse_cases("case x\nouts 1").unwrap_err(),
+        "line 2: unknown keyword outs"
+    );
+}
@@ -0,0 +1,226 @@
+use crate::IntcodeProgram;
+use std::cell::RefCell;
+use std::fmt::{Display, Formatter};
+use std::ops::Range;
+use std::rc::Rc;
+
+/// Rust code that handles loads and stores to a range of a program's memory, e.g. a framebuffer
+/// or a random number register. Offsets are relative to the start of the mapped range.
+///
+/// Devices have to be `Clone`, so a fork of the program gets its own copy to run with.
+pub trait Device: ForkDevice {
+    fn load(&mut self, offset: usize) -> i64;
+    fn store(&mut self, offset: usize, value: i64);
+    /// The value at `offset` without the side effects of a load, for tools that read the program's
+    /// memory without running it, like `mem_value`.
+    fn peek(&self, offset: usize) -> i64;
+}
+
+/// Copies a device for a fork of the program it's mapped into. Implemented for every `Clone`
+/// device.
+pub trait ForkDevice {
+    fn fork_device(&self) -> Rc<RefCell<dyn Device>>;
+}
+
+impl<T: Device + Clone + 'static> ForkDevice for T {
+    fn fork_device(&self) -> Rc<RefCell<dyn Device>> {
+        Rc::new(RefCell::new(self.clone()))
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub enum MapDeviceError {
+    /// The range has no addresses in it, e.g. it ends before it starts.
+    EmptyRange { range: Range<usize> },
+    Overlap {
+        range: Range<usize>,
+        /// The range of the device that's already mapped there.
+        mapped: Range<usize>,
+    },
+}
+
+impl Display for MapDeviceError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), std::fmt::Error> {
+        match self {
+            MapDeviceError::EmptyRange { range } => {
+                write!(f, "Can't map a device to {:?}, the range is empty", range)
+            }
+            MapDeviceError::Overlap { range, mapped } => write!(
+                f,
+                "Can't map a device to {:?}, it overlaps the device at {:?}",
+                range, mapped
+            ),
+        }
+    }
+}
+
+impl std::error::Error for MapDeviceError {}
+
+impl IntcodeProgram {
+    /// Maps the addresses in `range` to `device`, so every load and store there (including
+    /// instruction fetches) goes to the device instead of memory, and `mem_value` peeks at it. The range may be
+    /// past the end of the program's memory. Forks of this program get their own copy of the
+    /// device, so `device` only sees the loads and stores of this program.
+    pub fn map_device(
+        &mut self,
+        range: Range<usize>,
+        device: Rc<RefCell<dyn Device>>,
+    ) -> Result<(), MapDeviceError> {
+        if range.is_empty() {
+            return Err(MapDeviceError::EmptyRange { range });
+        }
+        self.memory
+            .map_device(range.clone(), device)
+            .map_err(|mapped| MapDeviceError::Overlap { range, mapped })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::device::{Device, MapDeviceError};
+    use crate::IntcodeProgram;
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    /// A grid of pixels, written one address per pixel.
+    #[derive(Clone)]
+    struct Framebuffer {
+        width: usize,
+        pixels: Vec<i64>,
+    }
+
+    impl Device for Framebuffer {
+        fn load(&mut self, offset: usize) -> i64 {
+            self.pixels[offset]
+        }
+
+        fn store(&mut self, offset: usize, value: i64) {
+            self.pixels[offset] = value;
+        }
+
+        fn peek(&self, offset: usize) -> i64 {
+            self.pixels[offset]
+        }
+    }
+
+    impl Framebuffer {
+        fn row(&self, y: usize) -> &[i64] {
+            &self.pixels[y * self.width..(y + 1) * self.width]
+        }
+    }
+
+    /// Returns the next number from a sequence on every load, and restarts it on a store.
+    #[derive(Clone)]
+    struct Counter(i64);
+
+    impl Device for Counter {
+        fn load(&mut self, _offset: usize) -> i64 {
+            self.0 += 1;
+            self.0
+        }
+
+        fn store(&mut self, _offset: usize, value: i64) {
+            self.0 = value;
+        }
+
+        fn peek(&self, _offset: usize) -> i64 {
+            self.0
+        }
+    }
+
+    #[test]
+    fn test_stores_go_to_device() {
+        let framebuffer = Rc::new(RefCell::new(Framebuffer {
+            width: 2,
+            pixels: vec![0; 4],
+        }));
+        // draws pixel (1, 1), then copies it to (0, 0)
+        let mut program = IntcodeProgram::init_from("1101,0,7,40003,1001,40003,1,40000,99");
+        program
+            .map_device(40000..40004, framebuffer.clone())
+            .unwrap();
+        program.run();
+
+        assert!(program.is_halted());
+        assert_eq!(framebuffer.borrow().row(0), &[8, 0]);
+        assert_eq!(framebuffer.borrow().row(1), &[0, 7]);
+        assert_eq!(program.mem_value(40000), 8);
+    }
+
+    #[test]
+    fn test_loads_come_from_device() {
+        // outputs the register three times, after resetting it to 10
+        let mut program = IntcodeProgram::init_from("1101,0,10,100,4,100,4,100,4,100,99");
+        program
+            .map_device(100..101, Rc::new(RefCell::new(Counter(0))))
+            .unwrap();
+        program.run();
+
+        let outputs: Vec<i64> = std::iter::from_fn(|| program.consume_output()).collect();
+        assert_eq!(outputs, vec![11, 12, 13]);
+        // the rest of memory is unaffected
+        assert_eq!(program.mem_value(101), 0);
+
+        // reading the register from outside doesn't advance it
+        assert_eq!(program.mem_value(100), 13);
+        assert_eq!(program.mem_value(100), 13);
+    }
+
+    #[test]
+    fn test_forks_copy_devices() {
+        let counter = Rc::new(RefCell::new(Counter(0)));
+        // outputs the register, then the one mapped to the same device
+        let mut program = IntcodeProgram::init_from("4,100,4,200,99");
+        program.map_device(100..101, counter.clone()).unwrap();
+        program.map_device(200..201, counter.clone()).unwrap();
+
+        let mut fork = program.fork();
+        fork.run();
+        program.run();
+        let fork_outputs: Vec<i64> = std::iter::from_fn(|| fork.consume_output()).collect();
+        let outputs: Vec<i64> = std::iter::from_fn(|| program.consume_output()).collect();
+        assert_eq!(fork_outputs, vec![1, 2]);
+        assert_eq!(outputs, vec![1, 2]);
+        assert_eq!(counter.borrow().0, 2);
+    }
+
+    #[test]
+    fn test_overlapping_devices() {
+        let mut program = IntcodeProgram::init_from("99");
+        let counter = Rc::new(RefCell::new(Counter(0)));
+        program.map_device(10..20, counter.clone()).unwrap();
+        program.map_device(20..21, counter.clone()).unwrap();
+
+        let error = program.map_device(5..11, counter).unwrap_err();
+        assert_eq!(
+            error,
+            MapDeviceError::Overlap {
+                range: 5..11,
+                mapped: 10..20,
+            }
+        );
+        assert_eq!(
+            error.to_string(),
+            "Can't map a device to 5..11, it overlaps the device at 10..20"
+        );
+    }
+
+    #[test]
+    #[allow(clippy::reversed_empty_ranges)]
+    fn test_empty_device_ranges() {
+        let mut program = IntcodeProgram::init_from("99");
+        let counter = Rc::new(RefCell::new(Counter(0)));
+        for range in [30..30, 30..20].iter() {
+            assert_eq!(
+                program.map_device(range.clone(), counter.clone()),
+                Err(MapDeviceError::EmptyRange {
+                    range: range.clone()
+                })
+            );
+        }
+        assert_eq!(
+            program.map_device(30..20, counter).unwrap_err().to_string(),
+            "Can't map a device to 30..20, the range is empty"
+        );
+    }
+}
@@ -0,0 +1,460 @@
+//! Differential testing of intcode backends: the same program runs on two [`IntcodeMachine`]s in
+//! lockstep, and the first difference in their state is reported.
+//!
+//! Both machines run to their next branch, or until they stop to wait for input, halt or fail, and
+//! are then compared: the result, the outputs produced, whether they're halted or waiting, the PC
+//! and a hash of memory (memory is only searched for the differing address once the hashes
+//! differ). So a divergence is always in the code between the PC where the machines last agreed and
+//! the next branch. If they agree and are waiting, they're given the next input and continue.
+
+use crate::machine::IntcodeMachine;
+use crate::symbols::SymbolTable;
+use crate::MAX_INTCODE_SIZE;
+use std::fmt::{Display, Formatter};
+
+/// The first difference between two machines.
+#[derive(Debug, PartialEq)]
+pub struct Divergence {
+    /// How many inputs both machines had been given, plus one.
+    pub run: usize,
+    /// The PC both machines were at the last time they agreed.
+    pub pc: usize,
+    pub kind: DivergenceKind,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum DivergenceKind {
+    /// One run failed and the other didn't, or they failed differently.
+    Error {
+        a: Option<String>,
+        b: Option<String>,
+    },
+    /// The `index`th output of the whole run is different, or only produced by one machine.
+    Output {
+        index: usize,
+        a: Option<i64>,
+        b: Option<i64>,
+    },
+    /// One machine halted and the other didn't.
+    Halted {
+        a: bool,
+        b: bool,
+    },
+    /// One machine is waiting for input and the other isn't.
+    AwaitingInput {
+        a: bool,
+        b: bool,
+    },
+    Pc {
+        a: usize,
+        b: usize,
+    },
+    Memory {
+        addr: usize,
+        a: i64,
+        b: i64,
+    },
+}
+
+impl Divergence {
+    /// Describes the divergence like `to_string`, but with addresses named from `symbols`.
+    pub fn describe(&self, symbols: &SymbolTable) -> String {
+        let name = |addr: usize| symbols.name(addr);
+        let kind = match &self.kind {
+            DivergenceKind::Error { a, b } => format!("errors differ: {:?} vs {:?}", a, b),
+            DivergenceKind::Output { index, a, b } => {
+                format!("output {} differs: {:?} vs {:?}", index, a, b)
+            }
+            DivergenceKind::Halted { a, b } => format!("halted differs: {} vs {}", a, b),
+            DivergenceKind::AwaitingInput { a, b } => {
+                format!("awaiting input differs: {} vs {}", a, b)
+            }
+            DivergenceKind::Pc { a, b } => format!("PC differs: {} vs {}", name(*a), name(*b)),
+            DivergenceKind::Memory { addr, a, b } => {
+                format!("memory at {} differs: {} vs {}", name(*addr), a, b)
+            }
+        };
+        format!("Run {}, after PC {}: {}", self.run, name(self.pc), kind)
+    }
+}
+
+impl Display for Divergence {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), std::fmt::Error> {
+        write!(f, "{}", self.describe(&SymbolTable::default()))
+    }
+}
+
+impl std::error::Error for Divergence {}
+
+/// Runs `a` and `b` in lockstep, giving them `inputs` one at a time whenever they wait for input.
+/// Stops when they halt, fail the same way or run out of inputs, and returns every output.
+pub fn lockstep<A, B>(a: &mut A, b: &mut B, inputs: &[i64]) -> Result<Vec<i64>, Divergence>
+where
+    A: IntcodeMachine,
+    B: IntcodeMachine,
+{
+    let mut outputs = Vec::new();
+    let mut inputs = inputs.iter();
+    let mut run = 1;
+    let mut pc = a.pc();
+
+    loop {
+        let diverged = |kind| Divergence { run, pc, kind };
+
+        let result_a = a.try_run_to_branch().map_err(|e| e.to_string()).err();
+        let result_b = b.try_run_to_branch().map_err(|e| e.to_string()).err();
+
+        loop {
+            let (output_a, output_b) = (a.consume_output(), b.consume_output());
+            if output_a != output_b {
+                return Err(diverged(DivergenceKind::Output {
+                    index: outputs.len(),
+                    a: output_a,
+                    b: output_b,
+                }));
+            }
+            match output_a {
+                Some(output) => outputs.push(output),
+                None => break,
+            }
+        }
+
+        if result_a != result_b {
+            return Err(diverged(DivergenceKind::Error {
+                a: result_a,
+                b: result_b,
+            }));
+        }
+        if a.is_halted() != b.is_halted() {
+            return Err(diverged(DivergenceKind::Halted {
+                a: a.is_halted(),
+                b: b.is_halted(),
+            }));
+        }
+        if a.pc() != b.pc() {
+            return Err(diverged(DivergenceKind::Pc {
+                a: a.pc(),
+                b: b.pc(),
+            }));
+        }
+        if a.is_awaiting_input() != b.is_awaiting_input() {
+            return Err(diverged(DivergenceKind::AwaitingInput {
+                a: a.is_awaiting_input(),
+                b: b.is_awaiting_input(),
+            }));
+        }
+        if a.memory_hash() != b.memory_hash() {
+            if let Some(addr) =
+                (0..MAX_INTCODE_SIZE).find(|addr| a.mem_value(*addr) != b.mem_value(*addr))
+            {
+                return Err(diverged(DivergenceKind::Memory {
+                    addr,
+                    a: a.mem_value(addr),
+                    b: b.mem_value(addr),
+                }));
+            }
+        }
+        pc = a.pc();
+
+        if result_a.is_some() || a.is_halted() {
+            break;
+        }
+        if a.is_awaiting_input() {
+            match inputs.next() {
+                Some(input) => {
+                    a.buffer_input(*input);
+                    b.buffer_input(*input);
+                    run += 1;
+                }
+                None => break,
+            }
+        }
+    }
+
+    Ok(outputs)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::differential::{lockstep, Divergence, DivergenceKind};
+    use crate::machine::IntcodeMachine;
+    use crate::memory::cell_hash;
+    use crate::symbols::SymbolTable;
+    use crate::{parse_intcode_input, IntcodeError, IntcodeProgram};
+    use std::collections::VecDeque;
+
+    /// A deliberately simple interpreter, to check the real one against.
+    struct Reference {
+        memory: Vec<i64>,
+        /// Kept up to date on every store, like `Memory`'s.
+        hash: u64,
+        pc: usize,
+        relative_base: i64,
+        inputs: VecDeque<i64>,
+        outputs: VecDeque<i64>,
+        is_halted: bool,
+        is_awaiting_input: bool,
+    }
+
+    impl Reference {
+        fn new(memory: &[i64]) -> Reference {
+            let mut reference = Reference {
+                memory: Vec::new(),
+                hash: 0,
+                pc: 0,
+                relative_base: 0,
+                inputs: VecDeque::new(),
+                outputs: VecDeque::new(),
+                is_halted: false,
+                is_awaiting_input: false,
+            };
+            for (addr, value) in memory.iter().enumerate() {
+                reference.store(addr, *value);
+            }
+            reference
+        }
+
+        fn store(&mut self, addr: usize, value: i64) {
+            if addr >= self.memory.len() {
+                self.memory.resize(addr + 1, 0);
+            }
+            self.hash ^= cell_hash(addr, self.memory[addr]) ^ cell_hash(addr, value);
+            self.memory[addr] = value;
+        }
+
+        fn addr(&mut self, param: usize) -> usize {
+            let mode = self.memory[self.pc] / [100, 1000, 10000][param] % 10;
+            let slot = self.pc + 1 + param;
+            let addr = match mode {
+                0 => self.memory[slot] as usize,
+                1 => slot,
+                2 => (self.relative_base + self.memory[slot]) as usize,
+                _ => panic!("Bad mode in {}", self.memory[self.pc]),
+            };
+            if addr >= self.memory.len() {
+                self.memory.resize(addr + 1, 0);
+            }
+            addr
+        }
+
+        fn param(&mut self, param: usize) -> i64 {
+            let addr = self.addr(param);
+            self.memory[addr]
+        }
+
+        fn run(&mut self, pause_at_branch: bool) -> Result<(), IntcodeError> {
+            loop {
+                match self.memory[self.pc] % 100 {
+                    1 | 2 | 7 | 8 => {
+                        let (a, b, c) = (self.param(0), self.param(1), self.addr(2));
+                        let value = match self.memory[self.pc] % 100 {
+                            1 => a + b,
+                            2 => a * b,
+                            7 => (a < b) as i64,
+                            _ => (a == b) as i64,
+                        };
+                        self.store(c, value);
+                        self.pc += 4;
+                    }
+                    3 => match self.inputs.pop_front() {
+                        Some(input) => {
+                            let addr = self.addr(0);
+                            self.store(addr, input);
+                            self.is_awaiting_input = false;
+                            self.pc += 2;
+                        }
+                        None => {
+                            self.is_awaiting_input = true;
+                            return Ok(());
+                        }
+                    },
+                    4 => {
+                        let output = self.param(0);
+                        self.outputs.push_back(output);
+                        self.pc += 2;
+                    }
+                    5 | 6 => {
+                        let (value, target) = (self.param(0), self.param(1));
+                        if (value != 0) == (self.memory[self.pc] % 100 == 5) {
+                            self.pc = target as usize;
+                        } else {
+                            self.pc += 3;
+                        }
+                        if pause_at_branch {
+                            return Ok(());
+                        }
+                    }
+                    9 => {
+                        self.relative_base += self.param(0);
+                        self.pc += 2;
+                    }
+                    99 => {
+                        self.is_halted = true;
+                        return Ok(());
+                    }
+                    opcode => panic!("Bad opcode {} at {}", opcode, self.pc),
+                }
+            }
+        }
+    }
+
+    impl IntcodeMachine for Reference {
+        fn try_run(&mut self) -> Result<(), IntcodeError> {
+            self.run(false)
+        }
+
+        fn try_run_to_branch(&mut self) -> Result<(), IntcodeError> {
+            self.run(true)
+        }
+
+        fn buffer_input(&mut self, input: i64) {
+            self.inputs.push_back(input);
+        }
+
+        fn consume_output(&mut self) -> Option<i64> {
+            self.outputs.pop_front()
+        }
+
+        fn mem_value(&self, addr: usize) -> i64 {
+            self.memory.get(addr).copied().unwrap_or(0)
+        }
+
+        fn memory_hash(&self) -> u64 {
+            self.hash
+        }
+
+        fn pc(&self) -> usize {
+            self.pc
+        }
+
+        fn is_awaiting_input(&self) -> bool {
+            self.is_awaiting_input
+        }
+
+        fn is_halted(&self) -> bool {
+            self.is_halted
+        }
+    }
+
+    fn ascii(text: &str) -> Vec<i64> {
+        text.bytes().map(|b| b as i64).collect()
+    }
+
+    fn check_day(intcode: &str, patches: &[(usize, i64)], inputs: &[i64]) -> Vec<i64> {
+        let mut memory = parse_intcode_input(intcode);
+        for (addr, value) in patches {
+            memory[*addr] = *value;
+        }
+
+        let mut program = IntcodeProgram::init(&memory, Vec::new());
+        let mut reference = Reference::new(&memory);
+        match lockstep(&mut program, &mut reference, inputs) {
+            Ok(outputs) => outputs,
+            Err(divergence) => panic!("{}", divergence),
+        }
+    }
+
+    #[test]
+    fn test_days_match_reference() {
+        let day2 = check_day(include_str!("../../day2/day2.txt"), &[(1, 12), (2, 2)], &[]);
+        assert!(day2.is_empty());
+        let day5 = check_day(include_str!("../../day5/day5.txt"), &[], &[5]);
+        assert_eq!(day5.len(), 1);
+        check_day(include_str!("../../day7/day7.txt"), &[], &[1, 0]);
+        check_day(include_str!("../../day7/day7.txt"), &[], &[5, 0, 10]);
+        let day9 = check_day(include_str!("../../day9/day9.txt"), &[], &[1]);
+        assert_eq!(day9.len(), 1);
+        check_day(
+            include_str!("../../day11/day11.txt"),
+            &[],
+            &[0, 1, 1, 0, 1, 1, 0, 0, 1],
+        );
+        check_day(
+            include_str!("../../day13/day13.txt"),
+            &[(0, 2)],
+            &[0, -1, 1, 1, 0],
+        );
+        check_day(
+            include_str!("../../day15/day15.txt"),
+            &[],
+            &[1, 2, 3, 4, 1, 1, 4, 4],
+        );
+        check_day(
+            include_str!("../../day17/day17.txt"),
+            &[(0, 2)],
+            &ascii("A\nR,4,L,2\nL,2\nR,4\nn\n"),
+        );
+        check_day(include_str!("../../day19/day19.txt"), &[], &[10, 20]);
+        check_day(
+            include_str!("../../day21/day21.txt"),
+            &[],
+            &ascii("NOT A J\nWALK\n"),
+        );
+        check_day(include_str!("../../day23/day23.txt"), &[], &[3, -1, -1]);
+        check_day(
+            include_str!("../../day25/day25.txt"),
+            &[],
+            &ascii("north\nsouth\ninv\n"),
+        );
+    }
+
+    #[test]
+    fn test_output_divergence() {
+        let mut a = IntcodeProgram::init_from("104,1,104,2,99");
+        let mut b = IntcodeProgram::init_from("104,1,104,3,99");
+        let divergence = lockstep(&mut a, &mut b, &[]).unwrap_err();
+        assert_eq!(
+            divergence,
+            Divergence {
+                run: 1,
+                pc: 0,
+                kind: DivergenceKind::Output {
+                    index: 1,
+                    a: Some(2),
+                    b: Some(3),
+                },
+            }
+        );
+        assert_eq!(
+            divergence.to_string(),
+            "Run 1, after PC 0: output 1 differs: Some(2) vs Some(3)"
+        );
+    }
+
+    #[test]
+    fn test_pc_and_memory_divergence() {
+        // both wait for input, at different addresses
+        let mut a = IntcodeProgram::init_from("3,5,99");
+        let mut b = IntcodeProgram::init_from("1105,1,3,3,5,99");
+        assert_eq!(
+            lockstep(&mut a, &mut b, &[7]).unwrap_err().to_string(),
+            "Run 1, after PC 0: PC differs: 0 vs 3"
+        );
+
+        // the same input is stored in different places
+        let mut a = IntcodeProgram::init_from("3,5,99");
+        let mut b = Reference::new(&parse_intcode_input("3,6,99"));
+        let divergence = lockstep(&mut a, &mut b, &[7]).unwrap_err();
+        assert_eq!(
+            divergence.to_string(),
+            "Run 1, after PC 0: memory at 1 differs: 5 vs 6"
+        );
+        let symbols = SymbolTable::parse("0..2 read_input function").unwrap();
+        assert_eq!(
+            divergence.describe(&symbols),
+            "Run 1, after PC read_input: memory at read_input+1 differs: 5 vs 6"
+        );
+    }
+
+    #[test]
+    fn test_divergence_after_branch() {
+        // counts down from 3, then reads an address that only the reference can grow memory to
+        let memory = parse_intcode_input("1001,12,-1,12,1005,12,0,4,40000,99,0,0,3");
+        let mut a = IntcodeProgram::init(&memory, Vec::new());
+        let mut b = Reference::new(&memory);
+        assert_eq!(
+            lockstep(&mut a, &mut b, &[]).unwrap_err().to_string(),
+            "Run 1, after PC 7: output 0 differs: None vs Some(0)"
+        );
+    }
+}
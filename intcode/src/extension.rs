@@ -0,0 +1,300 @@
+use crate::{IntcodeError, IntcodeProgram, InvalidOpCodeError, Op};
+use std::cell::Cell;
+use std::collections::HashMap;
+use std::rc::Rc;
+
+/// Custom opcodes that a program should accept in addition to the built-in ones. Opcodes that are
+/// in neither are still rejected.
+#[derive(Clone, Default)]
+pub struct OpcodeRegistry {
+    ops: HashMap<i8, CustomOp>,
+}
+
+#[derive(Clone)]
+struct CustomOp {
+    num_parameters: usize,
+    handler: Rc<dyn Fn(&mut CustomOpContext) -> CustomOpResult>,
+}
+
+/// What the program should do after a custom opcode's handler returns.
+#[derive(Debug, PartialEq)]
+pub enum CustomOpResult {
+    /// Continue with the instruction after this one.
+    Next,
+    /// Continue at the given address, which has to be in memory.
+    Jump(usize),
+    /// Stop running until more input is buffered; the instruction runs again when resumed.
+    AwaitInput,
+    Halt,
+}
+
+/// Access to the running program from a custom opcode's handler.
+///
+/// Reading or writing an address outside of memory reads 0 or does nothing, and the instruction
+/// fails with `IntcodeError::AddressOutOfBounds` once the handler returns.
+pub struct CustomOpContext<'a> {
+    program: &'a mut IntcodeProgram,
+    slots: &'a [usize],
+    /// The first address outside of memory that the handler tried to use.
+    bad_addr: Cell<Option<usize>>,
+}
+
+impl OpcodeRegistry {
+    pub fn new() -> OpcodeRegistry {
+        Default::default()
+    }
+
+    /// Registers `opcode` as an instruction taking `num_parameters` parameters, which are decoded
+    /// with the usual addressing modes before `handler` is called.
+    pub fn register<F>(
+        &mut self,
+        opcode: i8,
+        num_parameters: usize,
+        handler: F,
+    ) -> Result<(), InvalidOpCodeError>
+    where
+        F: Fn(&mut CustomOpContext) -> CustomOpResult + 'static,
+    {
+        if !(1..=98).contains(&opcode) {
+            return Err(InvalidOpCodeError {
+                message: format!("{} is not a two digit opcode", opcode),
+            });
+        }
+        if Op::from_opcode(opcode, &OpcodeRegistry::new()).is_ok() {
+            return Err(InvalidOpCodeError {
+                message: format!("{} is already a built-in opcode", opcode),
+            });
+        }
+        if self.ops.contains_key(&opcode) {
+            return Err(InvalidOpCodeError {
+                message: format!("{} is already registered", opcode),
+            });
+        }
+
+        self.ops.insert(
+            opcode,
+            CustomOp {
+                num_parameters,
+                handler: Rc::new(handler),
+            },
+        );
+        Ok(())
+    }
+
+    pub(crate) fn num_parameters(&self, opcode: i8) -> Option<usize> {
+        self.ops.get(&opcode).map(|op| op.num_parameters)
+    }
+}
+
+impl CustomOpContext<'_> {
+    pub fn num_parameters(&self) -> usize {
+        self.slots.len()
+    }
+
+    /// The value of parameter `i`, resolved according to its addressing mode.
+    pub fn param(&self, i: usize) -> i64 {
+        self.program.load(self.slots[i])
+    }
+
+    /// Stores `value` in the location given by parameter `i`.
+    pub fn set_param(&mut self, i: usize, value: i64) {
+        self.program.store(self.slots[i], value)
+    }
+
+    pub fn read(&self, addr: usize) -> i64 {
+        if !self.in_bounds(addr) {
+            return 0;
+        }
+        self.program.load(addr)
+    }
+
+    pub fn write(&mut self, addr: usize, value: i64) {
+        if self.in_bounds(addr) {
+            self.program.store(addr, value)
+        }
+    }
+
+    fn in_bounds(&self, addr: usize) -> bool {
+        if self.program.memory.contains(addr) {
+            return true;
+        }
+        if self.bad_addr.get().is_none() {
+            self.bad_addr.set(Some(addr));
+        }
+        false
+    }
+
+    pub fn input(&mut self) -> Option<i64> {
+        self.program.consume_input()
+    }
+
+    pub fn output(&mut self, value: i64) {
+        self.program.buffer_output(value)
+    }
+
+    pub fn pc(&self) -> usize {
+        self.program.pc
+    }
+
+    pub fn relative_base(&self) -> i64 {
+        self.program.relative_base
+    }
+}
+
+impl IntcodeProgram {
+    /// Accept the custom opcodes in `registry` from now on, replacing any registered before.
+    pub fn set_opcode_registry(&mut self, registry: OpcodeRegistry) {
+        self.opcodes = registry;
+    }
+
+    pub(crate) fn apply_custom(
+        &mut self,
+        opcode: i8,
+        slots: &[usize],
+    ) -> Result<CustomOpResult, IntcodeError> {
+        let handler = self.opcodes.ops[&opcode].handler.clone();
+        let mut context = CustomOpContext {
+            program: self,
+            slots,
+            bad_addr: Cell::new(None),
+        };
+        let result = handler(&mut context);
+        if let Some(addr) = context.bad_addr.get() {
+            return Err(IntcodeError::AddressOutOfBounds {
+                pc: self.pc,
+                addr: addr as i64,
+            });
+        }
+
+        match result {
+            CustomOpResult::Next => self.pc += slots.len() + 1,
+            CustomOpResult::Jump(pc) => {
+                self.check_bounds(pc as i64)?;
+                self.pc = pc
+            }
+            CustomOpResult::AwaitInput | CustomOpResult::Halt => {}
+        }
+        Ok(result)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::extension::{CustomOpContext, CustomOpResult, OpcodeRegistry};
+    use crate::IntcodeProgram;
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    #[test]
+    fn test_register_rejects_built_in_and_duplicate_opcodes() {
+        let mut registry = OpcodeRegistry::new();
+        assert!(registry.register(4, 1, |_| CustomOpResult::Next).is_err());
+        assert!(registry.register(99, 0, |_| CustomOpResult::Next).is_err());
+        assert!(registry.register(100, 0, |_| CustomOpResult::Next).is_err());
+        assert!(registry.register(42, 1, |_| CustomOpResult::Next).is_ok());
+        assert!(registry.register(42, 1, |_| CustomOpResult::Next).is_err());
+    }
+
+    #[test]
+    fn test_debug_print_opcode() {
+        let printed = Rc::new(RefCell::new(Vec::new()));
+        let printed_by_op = printed.clone();
+
+        let mut registry = OpcodeRegistry::new();
+        registry
+            .register(42, 1, move |ctx| {
+                printed_by_op.borrow_mut().push(ctx.param(0));
+                CustomOpResult::Next
+            })
+            .unwrap();
+
+        // print m[7], print immediate 5, then output m[7]
+        let mut program = IntcodeProgram::init_from("42,7,142,5,4,7,99,1234");
+        program.set_opcode_registry(registry);
+        program.run();
+
+        assert_eq!(*printed.borrow(), vec![1234, 5]);
+        assert_eq!(program.consume_output(), Some(1234));
+        assert!(program.is_halted());
+    }
+
+    #[test]
+    fn test_host_call_opcode_with_io() {
+        fn gcd(a: i64, b: i64) -> i64 {
+            if b == 0 {
+                a
+            } else {
+                gcd(b, a % b)
+            }
+        }
+
+        let mut registry = OpcodeRegistry::new();
+        registry
+            .register(50, 3, |ctx| {
+                let result = gcd(ctx.param(0), ctx.param(1));
+                ctx.set_param(2, result);
+                CustomOpResult::Next
+            })
+            .unwrap();
+        registry
+            .register(51, 0, |ctx| match ctx.input() {
+                Some(value) => {
+                    ctx.output(value * 2);
+                    CustomOpResult::Next
+                }
+                None => CustomOpResult::AwaitInput,
+            })
+            .unwrap();
+
+        // m[9] = gcd(84, 36), output m[9], then double an input
+        let mut program = IntcodeProgram::init_from("1150,84,36,9,4,9,51,99,0,0");
+        program.set_opcode_registry(registry);
+        program.run();
+        assert_eq!(program.consume_output(), Some(12));
+        assert!(program.is_awaiting_input());
+
+        program.buffer_input(21);
+        program.run();
+        assert_eq!(program.consume_output(), Some(42));
+        assert!(program.is_halted());
+    }
+
+    #[test]
+    fn test_handler_out_of_bounds() {
+        let run = |handler: fn(&mut CustomOpContext) -> CustomOpResult| {
+            let mut registry = OpcodeRegistry::new();
+            registry.register(42, 0, handler).unwrap();
+            let mut program = IntcodeProgram::init_from("42,99");
+            program.set_opcode_registry(registry);
+            program.try_run().unwrap_err().to_string()
+        };
+
+        let error = "PC 0: address 40000 is outside of memory";
+        assert_eq!(
+            run(|ctx| {
+                ctx.read(40000);
+                CustomOpResult::Next
+            }),
+            error
+        );
+        assert_eq!(
+            run(|ctx| {
+                ctx.write(40000, 1);
+                CustomOpResult::Next
+            }),
+            error
+        );
+        assert_eq!(run(|_| CustomOpResult::Jump(40000)), error);
+    }
+
+    #[test]
+    #[should_panic(expected = "Invalid opcode: 42")]
+    fn test_unregistered_opcode_still_errors() {
+        let mut registry = OpcodeRegistry::new();
+        registry.register(43, 0, |_| CustomOpResult::Halt).unwrap();
+
+        let mut program = IntcodeProgram::init_from("42,99");
+        program.set_opcode_registry(registry);
+        program.run();
+    }
+}
@@ -0,0 +1,206 @@
+use crate::memory::mix;
+use crate::IntcodeProgram;
+
+impl IntcodeProgram {
+    /// A hash of the program's state: memory, PC, relative base and the inputs and outputs that
+    /// are still buffered. Programs in the same state have the same fingerprint, e.g. to tell when
+    /// a search reaches a state it's already seen. The memory's part of it is kept up to date on
+    /// every store, so this is cheap as long as few inputs and outputs are buffered.
+    pub fn fingerprint(&self) -> u64 {
+        let mut hash = self.machine_hash();
+        hash = mix(hash ^ self.input_buf.len() as u64);
+        for input in self.input_buf.iter() {
+            hash = mix(hash ^ *input as u64);
+        }
+        hash = mix(hash ^ self.output_buf.len() as u64);
+        for output in self.output_buf.iter() {
+            hash = mix(hash ^ *output as u64);
+        }
+        hash
+    }
+
+    /// The part of the fingerprint that can change without any I/O.
+    fn machine_hash(&self) -> u64 {
+        let hash = mix(self.memory.hash() ^ self.pc as u64);
+        mix(hash ^ self.relative_base as u64)
+    }
+
+    /// Stops `run` with `IntcodeError::Cycle` when the program gets back into a state it was
+    /// already in without reading or writing anything since, as it would then loop forever.
+    /// Loads and stores to a mapped device count as I/O, since the device's state isn't part of
+    /// the program's.
+    ///
+    /// States are compared by fingerprint, against a state saved after 1, 2, 4, 8, ... steps, so a
+    /// cycle may go around a few times before it's detected.
+    pub fn set_cycle_detection(&mut self, enabled: bool) {
+        self.cycle_detector = if enabled {
+            Some(CycleDetector::new(self.machine_hash()))
+        } else {
+            None
+        };
+    }
+
+    /// Changes whenever the program reads or writes anything: the number of buffered inputs and
+    /// outputs, and of loads and stores to devices.
+    pub(crate) fn io_state(&self) -> (usize, usize, u64) {
+        (
+            self.input_buf.len(),
+            self.output_buf.len(),
+            self.memory.device_accesses(),
+        )
+    }
+
+    /// Called after every instruction while cycle detection is enabled. Returns true if the
+    /// program is in a cycle.
+    pub(crate) fn check_for_cycle(&mut self, did_io: bool) -> bool {
+        let hash = self.machine_hash();
+        match &mut self.cycle_detector {
+            Some(detector) if did_io => {
+                *detector = CycleDetector::new(hash);
+                false
+            }
+            Some(detector) => detector.is_repeat(hash),
+            None => false,
+        }
+    }
+}
+
+/// Brent's cycle detection: the state is saved after 1, 2, 4, 8, ... steps, and a cycle is found
+/// when a later state is the saved one. This only remembers one state, and finds a cycle of length
+/// `n` that starts after `m` steps in at most about `2 * max(m, n) + n` steps.
+#[derive(Clone)]
+pub(crate) struct CycleDetector {
+    saved: u64,
+    steps: u64,
+    limit: u64,
+}
+
+impl CycleDetector {
+    pub(crate) fn new(hash: u64) -> CycleDetector {
+        CycleDetector {
+            saved: hash,
+            steps: 0,
+            limit: 1,
+        }
+    }
+
+    pub(crate) fn is_repeat(&mut self, hash: u64) -> bool {
+        if hash == self.saved {
+            return true;
+        }
+        self.steps += 1;
+        if self.steps == self.limit {
+            self.saved = hash;
+            self.steps = 0;
+            self.limit *= 2;
+        }
+        false
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::device::Device;
+    use crate::IntcodeProgram;
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    #[test]
+    fn test_equal_states_have_equal_fingerprints() {
+        let program = IntcodeProgram::init_from("3,9,1001,9,1,9,4,9,99,0");
+        let mut a = program.fork();
+        let mut b = program.fork();
+        assert_eq!(a.fingerprint(), b.fingerprint());
+
+        a.buffer_input(1);
+        assert_ne!(a.fingerprint(), b.fingerprint());
+        b.buffer_input(2);
+        assert_ne!(a.fingerprint(), b.fingerprint());
+
+        a.run();
+        b.run();
+        assert_eq!(a.consume_output(), Some(2));
+        assert_eq!(b.consume_output(), Some(3));
+        assert_ne!(a.fingerprint(), b.fingerprint());
+    }
+
+    #[test]
+    fn test_fingerprint_follows_stores() {
+        // sets m[9] to 5, then back to 0
+        let mut program = IntcodeProgram::init_from("1101,0,5,9,1101,0,0,9,99,0");
+        let mut halted = IntcodeProgram::init_from("1101,0,5,9,1101,0,0,9,99,0");
+        halted.set_pc(8);
+        assert_ne!(program.fingerprint(), halted.fingerprint());
+
+        program.run();
+        assert_eq!(program.fingerprint(), halted.fingerprint());
+        assert_ne!(
+            program.fingerprint(),
+            IntcodeProgram::init_from("1101,0,5,9,1101,0,0,9,99,1").fingerprint()
+        );
+    }
+
+    #[test]
+    fn test_cycle_detection() {
+        let mut program = IntcodeProgram::init_from("1105,1,0");
+        program.set_cycle_detection(true);
+        assert_eq!(
+            program.try_run().unwrap_err().to_string(),
+            "PC 0: the program is stuck in a loop"
+        );
+
+        // swaps two values forever
+        let mut program = IntcodeProgram::init_from(
+            "1001,20,0,22,1001,21,0,20,1001,22,0,21,1105,1,0,0,0,0,0,0,1,2",
+        );
+        program.set_cycle_detection(true);
+        assert!(program.try_run().is_err());
+    }
+
+    #[test]
+    fn test_loops_that_make_progress_run() {
+        // counts down from 5
+        let mut program = IntcodeProgram::init_from("1001,10,-1,10,1005,10,0,99,0,0,5");
+        program.set_cycle_detection(true);
+        program.run();
+        assert!(program.is_halted());
+
+        // the same state, but after reading an input each time
+        let mut program = IntcodeProgram::init_from("3,10,1105,1,0");
+        program.set_cycle_detection(true);
+        vec![1, 1, 1]
+            .into_iter()
+            .for_each(|input| program.buffer_input(input));
+        program.run();
+        assert!(program.is_awaiting_input());
+    }
+
+    /// Returns the next number from a sequence on every load.
+    #[derive(Clone)]
+    struct Counter(i64);
+
+    impl Device for Counter {
+        fn load(&mut self, _offset: usize) -> i64 {
+            self.0 += 1;
+            self.0
+        }
+
+        fn store(&mut self, _offset: usize, _value: i64) {}
+
+        fn peek(&self, _offset: usize) -> i64 {
+            self.0
+        }
+    }
+
+    #[test]
+    fn test_polling_a_device_is_not_a_cycle() {
+        // polls the device until it reads 5
+        let mut program = IntcodeProgram::init_from("1008,100,5,101,1006,101,0,99");
+        program
+            .map_device(100..101, Rc::new(RefCell::new(Counter(0))))
+            .unwrap();
+        program.set_cycle_detection(true);
+        program.run();
+        assert!(program.is_halted());
+    }
+}
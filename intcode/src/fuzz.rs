@@ -0,0 +1,204 @@
+//! Property tests that run the interpreter on randomly generated programs. The generator and its
+//! random numbers are self-contained, so every case is reproducible from its seed and runs offline.
+//!
+//! For each program, these hold:
+//!
+//! * The interpreter never panics: anything it can't execute is an `IntcodeError`.
+//! * Executing one instruction at a time ends in the same state, with the same error, as `run`.
+//! * A fork taken partway through ends in the same state as the program it was forked from.
+//! * Saving the program as an image and loading it gives back the same memory.
+//!
+//! There's no assembler or disassembler in the crate yet, so there's no round trip through them to
+//! check.
+
+use crate::image::{load_image, save_image, IntcodeImage};
+use crate::{IntcodeError, IntcodeProgram, IntcodeResult, MAX_INTCODE_SIZE};
+use std::collections::VecDeque;
+use std::panic;
+
+const NUM_CASES: u64 = 500;
+/// Programs that haven't stopped after this many instructions are assumed to loop forever.
+const MAX_STEPS: usize = 2000;
+
+/// xorshift64*, which is plenty for generating test programs.
+struct Rng(u64);
+
+impl Rng {
+    fn new(seed: u64) -> Rng {
+        // the state must never be zero
+        Rng(seed.wrapping_mul(0x9E37_79B9_7F4A_7C15) | 1)
+    }
+
+    fn next(&mut self) -> u64 {
+        self.0 ^= self.0 >> 12;
+        self.0 ^= self.0 << 25;
+        self.0 ^= self.0 >> 27;
+        self.0.wrapping_mul(0x2545_F491_4F6C_DD1D)
+    }
+
+    fn below(&mut self, n: usize) -> usize {
+        (self.next() % n as u64) as usize
+    }
+
+    fn range(&mut self, low: i64, high: i64) -> i64 {
+        low + (self.next() % (high - low) as u64) as i64
+    }
+}
+
+/// A straight-line sequence of valid instructions ending in a halt, followed by some data. Jumps
+/// and operands make most of them do something more interesting than that, and a few have a word
+/// replaced with anything at all.
+fn generate_program(rng: &mut Rng) -> Vec<i64> {
+    // arithmetic is more common than anything else, as in real programs
+    const OPCODES: [i64; 12] = [1, 1, 2, 2, 3, 4, 5, 6, 7, 8, 8, 9];
+    let num_instructions = 1 + rng.below(20);
+    let size = (num_instructions * 4 + 8) as i64;
+
+    let mut memory = Vec::new();
+    for _ in 0..num_instructions {
+        let opcode = OPCODES[rng.below(OPCODES.len())];
+        let (num_reads, num_writes) = match opcode {
+            1 | 2 | 7 | 8 => (2, 1),
+            3 => (0, 1),
+            4 | 9 => (1, 0),
+            _ => (2, 0),
+        };
+
+        let mut instruction = opcode;
+        let mut operands = Vec::new();
+        for i in 0..num_reads + num_writes {
+            // writes never use immediate mode
+            let mode = if i < num_reads {
+                rng.below(3)
+            } else {
+                [0, 2][rng.below(2)]
+            };
+            instruction += mode as i64 * [100, 1000, 10000][i];
+            operands.push(match mode {
+                0 => rng.range(0, size),
+                1 => rng.range(-5, size),
+                _ => rng.range(0, 8),
+            });
+        }
+        memory.push(instruction);
+        memory.extend(operands);
+    }
+    memory.push(99);
+    for _ in 0..rng.below(8) {
+        memory.push(rng.range(-10, 100));
+    }
+
+    if rng.below(10) == 0 {
+        let addr = rng.below(memory.len());
+        memory[addr] = rng.next() as i64;
+    }
+    memory
+}
+
+fn generate_inputs(rng: &mut Rng) -> Vec<i64> {
+    (0..rng.below(5)).map(|_| rng.range(-10, 10)).collect()
+}
+
+/// Executes one instruction at a time until the program stops, or `None` if it doesn't stop
+/// within `max_steps`.
+fn step_until_stopped(
+    program: &mut IntcodeProgram,
+    max_steps: usize,
+) -> Option<Result<(), IntcodeError>> {
+    for _ in 0..max_steps {
+        match program.step() {
+            Ok(IntcodeResult::ExecutedInstruction) => {}
+            Ok(_) => return Some(Ok(())),
+            Err(e) => return Some(Err(e)),
+        }
+    }
+    None
+}
+
+#[derive(Debug, PartialEq)]
+struct State {
+    pc: usize,
+    relative_base: i64,
+    is_halted: bool,
+    is_awaiting_input: bool,
+    inputs: VecDeque<i64>,
+    outputs: VecDeque<i64>,
+    memory: Vec<i64>,
+}
+
+fn state(program: &IntcodeProgram) -> State {
+    State {
+        pc: program.pc,
+        relative_base: program.relative_base,
+        is_halted: program.is_halted,
+        is_awaiting_input: program.is_awaiting_input,
+        inputs: program.input_buf.clone(),
+        outputs: program.output_buf.clone(),
+        memory: (0..MAX_INTCODE_SIZE)
+            .map(|addr| program.mem_value(addr))
+            .collect(),
+    }
+}
+
+fn error_message(result: Result<(), IntcodeError>) -> Option<String> {
+    result.err().map(|e| e.to_string())
+}
+
+fn check_case(seed: u64) {
+    let mut rng = Rng::new(seed);
+    let memory = generate_program(&mut rng);
+    let inputs = generate_inputs(&mut rng);
+
+    let image = load_image(&save_image(&IntcodeImage::from_memory(&memory))).unwrap();
+    assert_eq!(image.memory(), memory, "image round trip");
+
+    let mut stepped = IntcodeProgram::init(&memory, inputs.clone());
+    let stepped_result = match step_until_stopped(&mut stepped, MAX_STEPS) {
+        Some(result) => error_message(result),
+        // running it would never return
+        None => return,
+    };
+    let expected = state(&stepped);
+
+    let mut run = IntcodeProgram::init(&memory, inputs.clone());
+    assert_eq!(error_message(run.try_run()), stepped_result, "run");
+    assert_eq!(state(&run), expected, "run");
+
+    let mut original = IntcodeProgram::init(&memory, inputs);
+    let fork_after = rng.below(MAX_STEPS);
+    let fork_result = match step_until_stopped(&mut original, fork_after) {
+        Some(result) => error_message(result),
+        None => {
+            let mut fork = original.fork();
+            assert_eq!(error_message(fork.try_run()), stepped_result, "fork");
+            assert_eq!(state(&fork), expected, "fork");
+            error_message(original.try_run())
+        }
+    };
+    assert_eq!(fork_result, stepped_result, "forked from");
+    assert_eq!(state(&original), expected, "forked from");
+}
+
+#[test]
+fn test_random_programs() {
+    for seed in 0..NUM_CASES {
+        if let Err(e) = panic::catch_unwind(|| check_case(seed)) {
+            let message = e
+                .downcast_ref::<String>()
+                .cloned()
+                .or_else(|| e.downcast_ref::<&str>().map(|s| s.to_string()));
+            panic!("Case {} failed: {:?}", seed, message);
+        }
+    }
+}
+
+#[test]
+fn test_rng_is_deterministic() {
+    let mut a = Rng::new(7);
+    let mut b = Rng::new(7);
+    assert_eq!(generate_program(&mut a), generate_program(&mut b));
+    assert_ne!(
+        generate_program(&mut Rng::new(1)),
+        generate_program(&mut Rng::new(2))
+    );
+}
@@ -0,0 +1,368 @@
+//! A compact binary container for intcode programs.
+//!
+//! Layout, with every integer stored as a LEB128 varint:
+//!
+//! ```text
+//! magic         b"ICIM"
+//! version       1 byte
+//! flags         1 byte, bit 0 set if there's a metadata section
+//! base address  varint
+//! word count    varint
+//! words         zig-zag encoded varints
+//! metadata      name, notes, then a count of (address, name) symbols; strings are a byte length
+//!               followed by UTF-8
+//! ```
+
+use crate::MAX_INTCODE_SIZE;
+use std::fmt::{Display, Formatter};
+
+const MAGIC: &[u8; 4] = b"ICIM";
+const VERSION: u8 = 1;
+const FLAG_METADATA: u8 = 1;
+
+#[derive(Debug, PartialEq)]
+pub struct IntcodeImage {
+    /// Address that the first word is loaded at.
+    pub base_address: usize,
+    pub words: Vec<i64>,
+    pub metadata: Option<Metadata>,
+}
+
+#[derive(Debug, Default, PartialEq)]
+pub struct Metadata {
+    pub name: String,
+    /// Free-form notes, e.g. which addresses to patch before running.
+    pub notes: String,
+    pub symbols: Vec<(usize, String)>,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum ImageError {
+    BadMagic,
+    UnsupportedVersion(u8),
+    /// The data ended in the middle of the named field.
+    Truncated(&'static str),
+    /// A varint was longer than 64 bits.
+    Overflow(&'static str),
+    InvalidUtf8(&'static str),
+    /// The words wouldn't fit in memory at the base address.
+    TooLarge {
+        base_address: u64,
+        num_words: u64,
+    },
+    /// There were this many bytes left over after the image.
+    TrailingBytes(usize),
+}
+
+impl Display for ImageError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), std::fmt::Error> {
+        match self {
+            ImageError::BadMagic => write!(f, "not an intcode image"),
+            ImageError::UnsupportedVersion(v) => write!(f, "unsupported image version {}", v),
+            ImageError::Truncated(field) => write!(f, "image truncated in {}", field),
+            ImageError::Overflow(field) => write!(f, "varint too long in {}", field),
+            ImageError::InvalidUtf8(field) => write!(f, "invalid UTF-8 in {}", field),
+            ImageError::TooLarge {
+                base_address,
+                num_words,
+            } => write!(
+                f,
+                "{} words at address {} don't fit in memory, which holds {}",
+                num_words, base_address, MAX_INTCODE_SIZE
+            ),
+            ImageError::TrailingBytes(len) => write!(f, "{} bytes after the end of the image", len),
+        }
+    }
+}
+
+impl std::error::Error for ImageError {}
+
+impl IntcodeImage {
+    pub fn from_memory(memory: &[i64]) -> IntcodeImage {
+        IntcodeImage {
+            base_address: 0,
+            words: memory.to_vec(),
+            metadata: None,
+        }
+    }
+
+    /// The program's memory, with the words placed at the base address.
+    pub fn memory(&self) -> Vec<i64> {
+        let mut memory = vec![0; self.base_address];
+        memory.extend_from_slice(&self.words);
+        memory
+    }
+}
+
+/// Checks for the image magic, e.g. to tell an image from a text program.
+pub fn is_image(data: &[u8]) -> bool {
+    data.starts_with(MAGIC)
+}
+
+pub fn save_image(image: &IntcodeImage) -> Vec<u8> {
+    let mut out = Vec::new();
+    out.extend_from_slice(MAGIC);
+    out.push(VERSION);
+    out.push(if image.metadata.is_some() {
+        FLAG_METADATA
+    } else {
+        0
+    });
+
+    write_varint(&mut out, image.base_address as u64);
+    write_varint(&mut out, image.words.len() as u64);
+    for word in image.words.iter() {
+        write_varint(&mut out, zigzag(*word));
+    }
+
+    if let Some(metadata) = &image.metadata {
+        write_string(&mut out, &metadata.name);
+        write_string(&mut out, &metadata.notes);
+        write_varint(&mut out, metadata.symbols.len() as u64);
+        for (addr, name) in metadata.symbols.iter() {
+            write_varint(&mut out, *addr as u64);
+            write_string(&mut out, name);
+        }
+    }
+    out
+}
+
+pub fn load_image(data: &[u8]) -> Result<IntcodeImage, ImageError> {
+    if !is_image(data) {
+        return Err(ImageError::BadMagic);
+    }
+    let mut reader = Reader {
+        data,
+        pos: MAGIC.len(),
+    };
+
+    let version = reader.byte("version")?;
+    if version != VERSION {
+        return Err(ImageError::UnsupportedVersion(version));
+    }
+    let flags = reader.byte("flags")?;
+
+    let base_address = reader.varint("base address")?;
+    let num_words = reader.varint("word count")?;
+    if base_address.saturating_add(num_words) > MAX_INTCODE_SIZE as u64 {
+        return Err(ImageError::TooLarge {
+            base_address,
+            num_words,
+        });
+    }
+    let (base_address, num_words) = (base_address as usize, num_words as usize);
+
+    // don't trust the count for preallocating more than the data could possibly hold
+    let mut words = Vec::with_capacity(num_words.min(data.len()));
+    for _ in 0..num_words {
+        words.push(unzigzag(reader.varint("words")?));
+    }
+
+    let metadata = if flags & FLAG_METADATA != 0 {
+        let name = reader.string("name")?;
+        let notes = reader.string("notes")?;
+        let num_symbols = reader.varint("symbol count")? as usize;
+        let mut symbols = Vec::with_capacity(num_symbols.min(data.len()));
+        for _ in 0..num_symbols {
+            let addr = reader.varint("symbols")? as usize;
+            symbols.push((addr, reader.string("symbols")?));
+        }
+        Some(Metadata {
+            name,
+            notes,
+            symbols,
+        })
+    } else {
+        None
+    };
+
+    if reader.pos < data.len() {
+        return Err(ImageError::TrailingBytes(data.len() - reader.pos));
+    }
+    Ok(IntcodeImage {
+        base_address,
+        words,
+        metadata,
+    })
+}
+
+fn zigzag(value: i64) -> u64 {
+    ((value << 1) ^ (value >> 63)) as u64
+}
+
+fn unzigzag(value: u64) -> i64 {
+    ((value >> 1) as i64) ^ -((value & 1) as i64)
+}
+
+fn write_varint(out: &mut Vec<u8>, mut value: u64) {
+    while value >= 0x80 {
+        out.push((value as u8 & 0x7f) | 0x80);
+        value >>= 7;
+    }
+    out.push(value as u8);
+}
+
+fn write_string(out: &mut Vec<u8>, s: &str) {
+    write_varint(out, s.len() as u64);
+    out.extend_from_slice(s.as_bytes());
+}
+
+struct Reader<'a> {
+    data: &'a [u8],
+    pos: usize,
+}
+
+impl Reader<'_> {
+    fn byte(&mut self, field: &'static str) -> Result<u8, ImageError> {
+        let byte = *self
+            .data
+            .get(self.pos)
+            .ok_or(ImageError::Truncated(field))?;
+        self.pos += 1;
+        Ok(byte)
+    }
+
+    fn varint(&mut self, field: &'static str) -> Result<u64, ImageError> {
+        let mut value: u64 = 0;
+        let mut shift = 0;
+        loop {
+            let byte = self.byte(field)?;
+            if shift == 63 && byte > 1 {
+                return Err(ImageError::Overflow(field));
+            }
+            value |= ((byte & 0x7f) as u64) << shift;
+            if byte & 0x80 == 0 {
+                return Ok(value);
+            }
+            shift += 7;
+            if shift > 63 {
+                return Err(ImageError::Overflow(field));
+            }
+        }
+    }
+
+    fn string(&mut self, field: &'static str) -> Result<String, ImageError> {
+        let len = self.varint(field)? as usize;
+        let end = self
+            .pos
+            .checked_add(len)
+            .filter(|end| *end <= self.data.len())
+            .ok_or(ImageError::Truncated(field))?;
+        let bytes = &self.data[self.pos..end];
+        self.pos = end;
+        String::from_utf8(bytes.to_vec()).map_err(|_| ImageError::InvalidUtf8(field))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::image::*;
+    use crate::parse_intcode_input;
+
+    #[test]
+    fn test_zigzag() {
+        for value in [0, 1, -1, 2, -2, 1125899906842624, i64::MAX, i64::MIN].iter() {
+            assert_eq!(unzigzag(zigzag(*value)), *value);
+        }
+        assert_eq!(zigzag(0), 0);
+        assert_eq!(zigzag(-1), 1);
+        assert_eq!(zigzag(1), 2);
+    }
+
+    #[test]
+    fn test_round_trip_without_metadata() {
+        let image = IntcodeImage::from_memory(&[109, 1, 204, -1, 1001, 100, 1, 100, 99]);
+        let data = save_image(&image);
+        assert!(is_image(&data));
+        assert_eq!(load_image(&data), Ok(image));
+    }
+
+    #[test]
+    fn test_round_trip_with_metadata() {
+        let image = IntcodeImage {
+            base_address: 10,
+            words: vec![1102, 34915192, 34915192, 7, 4, 7, 99, 0, i64::MIN, i64::MAX],
+            metadata: Some(Metadata {
+                name: "day9 example".to_string(),
+                notes: "outputs a 16 digit number".to_string(),
+                symbols: vec![(7, "result".to_string()), (16, "∞".to_string())],
+            }),
+        };
+        let data = save_image(&image);
+        let loaded = load_image(&data).unwrap();
+        assert_eq!(loaded, image);
+        assert_eq!(loaded.memory()[..11], [0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1102]);
+    }
+
+    #[test]
+    fn test_image_is_smaller_than_text() {
+        let text = include_str!("../../day9/day9.txt");
+        let image = IntcodeImage::from_memory(&parse_intcode_input(text));
+        let data = save_image(&image);
+        assert!(data.len() < text.len() / 2);
+        assert_eq!(load_image(&data).unwrap().words, image.words);
+    }
+
+    #[test]
+    fn test_load_errors() {
+        assert_eq!(load_image(b"1,0,0,0,99"), Err(ImageError::BadMagic));
+        assert_eq!(
+            load_image(b"ICIM\x02\x00"),
+            Err(ImageError::UnsupportedVersion(2))
+        );
+
+        let data = save_image(&IntcodeImage::from_memory(&[1, 2, 300]));
+        assert_eq!(
+            load_image(&data[..data.len() - 1]),
+            Err(ImageError::Truncated("words"))
+        );
+
+        let mut too_long = b"ICIM\x01\x00".to_vec();
+        too_long.extend_from_slice(&[0xff; 11]);
+        assert_eq!(
+            load_image(&too_long),
+            Err(ImageError::Overflow("base address"))
+        );
+
+        let mut metadata = IntcodeImage::from_memory(&[99]);
+        metadata.metadata = Some(Default::default());
+        let mut data = save_image(&metadata);
+        data.truncate(data.len() - 1);
+        assert_eq!(
+            load_image(&data),
+            Err(ImageError::Truncated("symbol count"))
+        );
+
+        let mut data = save_image(&IntcodeImage::from_memory(&[99]));
+        data.extend_from_slice(b"\n\n");
+        assert_eq!(load_image(&data), Err(ImageError::TrailingBytes(2)));
+    }
+
+    #[test]
+    fn test_load_too_large() {
+        let image = |base_address: usize, num_words: usize| IntcodeImage {
+            base_address,
+            words: vec![0; num_words],
+            metadata: None,
+        };
+        assert!(load_image(&save_image(&image(MAX_INTCODE_SIZE - 2, 2))).is_ok());
+        assert_eq!(
+            load_image(&save_image(&image(MAX_INTCODE_SIZE - 1, 2))),
+            Err(ImageError::TooLarge {
+                base_address: MAX_INTCODE_SIZE as u64 - 1,
+                num_words: 2
+            })
+        );
+
+        // a base address too big to allocate memory up to
+        let mut data = b"ICIM\x01\x00".to_vec();
+        data.extend_from_slice(&[0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0x7f, 0x00]);
+        assert_eq!(
+            load_image(&data),
+            Err(ImageError::TooLarge {
+                base_address: (1 << 63) - 1,
+                num_words: 0
+            })
+        );
+    }
+}
@@ -0,0 +1,118 @@
+use crate::{AddressingMode, Instruction, Op};
+use std::fmt::{Display, Formatter};
+
+/// The instructions a program may use, following the days the intcode spec was extended on.
+/// Each level includes everything from the ones before it.
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq, PartialOrd, Ord)]
+pub enum InstructionSet {
+    /// Add, multiply and halt, with position mode parameters only.
+    Day2,
+    /// Adds input/output, jumps, comparisons and immediate mode parameters.
+    Day5,
+    /// Adds relative mode parameters and adjusting the relative base.
+    #[default]
+    Day9,
+}
+
+impl InstructionSet {
+    /// Checks that `inst` is part of this instruction set, returning a description of the first
+    /// thing it uses that isn't. Custom opcodes are always allowed, since they have to be
+    /// registered explicitly.
+    pub(crate) fn check(&self, inst: &Instruction) -> Result<(), &'static str> {
+        let op_level = match inst.op {
+            Op::Add | Op::Mul | Op::Halt | Op::Custom(..) => InstructionSet::Day2,
+            Op::Input | Op::Output | Op::Jit | Op::Jif | Op::Lt | Op::Eq => InstructionSet::Day5,
+            Op::RelBaseOffset => InstructionSet::Day9,
+        };
+        if op_level > *self {
+            return Err(inst.op.name());
+        }
+
+        for mode in inst.addr_modes.iter() {
+            let (mode_level, name) = match mode {
+                AddressingMode::Position => (InstructionSet::Day2, "position mode"),
+                AddressingMode::Immediate => (InstructionSet::Day5, "immediate mode"),
+                AddressingMode::Relative => (InstructionSet::Day9, "relative mode"),
+            };
+            if mode_level > *self {
+                return Err(name);
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Display for InstructionSet {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), std::fmt::Error> {
+        let day = match self {
+            InstructionSet::Day2 => 2,
+            InstructionSet::Day5 => 5,
+            InstructionSet::Day9 => 9,
+        };
+        write!(f, "day {}", day)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::instruction_set::InstructionSet;
+    use crate::{IntcodeError, IntcodeProgram};
+
+    fn run_with(instruction_set: InstructionSet, intcode: &str) -> Result<(), IntcodeError> {
+        let mut program = IntcodeProgram::init_from(intcode);
+        program.set_instruction_set(instruction_set);
+        program.buffer_input(8);
+        program.try_run()
+    }
+
+    #[test]
+    fn test_day2_program_runs_at_every_level() {
+        let day2_example = "1,9,10,3,2,3,11,0,99,30,40,50";
+        assert!(run_with(InstructionSet::Day2, day2_example).is_ok());
+        assert!(run_with(InstructionSet::Day5, day2_example).is_ok());
+        assert!(run_with(InstructionSet::Day9, day2_example).is_ok());
+    }
+
+    #[test]
+    fn test_day2_rejects_io_and_immediate_mode() {
+        let io = run_with(InstructionSet::Day2, "3,0,4,0,99");
+        assert_eq!(
+            io.unwrap_err().to_string(),
+            "PC 0: input (instruction 3) is not part of the day 2 instruction set"
+        );
+
+        let immediate = run_with(InstructionSet::Day2, "1,0,0,0,1101,1,1,0,99");
+        assert_eq!(
+            immediate.unwrap_err().to_string(),
+            "PC 4: immediate mode (instruction 1101) is not part of the day 2 instruction set"
+        );
+    }
+
+    #[test]
+    fn test_day5_rejects_relative_base() {
+        // is the input equal to 8?
+        assert!(run_with(InstructionSet::Day5, "3,3,1108,-1,8,3,4,3,99").is_ok());
+
+        let result = run_with(InstructionSet::Day5, "109,19,204,-34,99");
+        assert_eq!(
+            result.unwrap_err().to_string(),
+            "PC 0: relative base offset (instruction 109) is not part of the day 5 instruction set"
+        );
+
+        let result = run_with(InstructionSet::Day5, "203,0,99");
+        assert_eq!(
+            result.unwrap_err().to_string(),
+            "PC 0: relative mode (instruction 203) is not part of the day 5 instruction set"
+        );
+    }
+
+    #[test]
+    fn test_rejected_instruction_is_not_executed() {
+        let mut program = IntcodeProgram::init_from("1,0,0,0,3,0,99");
+        program.set_instruction_set(InstructionSet::Day2);
+        assert!(program.try_run().is_err());
+        assert_eq!(program.mem_value(0), 2);
+        assert!(!program.is_halted());
+        assert!(!program.is_awaiting_input());
+    }
+}
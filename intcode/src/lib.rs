@@ -1,21 +1,72 @@
 extern crate log;
 
+pub mod codegen;
+pub mod compiled;
+#[cfg(test)]
+mod conformance;
+pub mod device;
+pub mod differential;
+pub mod extension;
+pub mod fingerprint;
+#[cfg(test)]
+mod fuzz;
+pub mod image;
+pub mod instruction_set;
+pub mod limits;
+pub mod loader;
+pub mod machine;
+mod memory;
+pub mod network;
+pub mod observer;
+pub mod outputs;
+pub mod search;
+#[cfg(feature = "async")]
+pub mod stream;
+pub mod strings;
+pub mod symbols;
+pub mod taint;
+pub mod transcript;
+
+use crate::extension::{CustomOpResult, OpcodeRegistry};
+use crate::fingerprint::CycleDetector;
+use crate::instruction_set::InstructionSet;
+use crate::limits::{Limit, Limits};
+use crate::loader::load_intcode;
+use crate::memory::Memory;
+use crate::observer::Observer;
 use log::debug;
+use std::cell::RefCell;
 use std::collections::VecDeque;
 use std::fmt::{Display, Formatter};
+use std::rc::Rc;
+use std::time::Instant;
 
 #[derive(Clone)]
 pub struct IntcodeProgram {
-    memory: Vec<i64>,
+    memory: Memory,
     input_buf: VecDeque<i64>,
     output_buf: VecDeque<i64>,
     pc: usize,
     relative_base: i64,
     is_halted: bool,
     is_awaiting_input: bool,
+    opcodes: OpcodeRegistry,
+    instruction_set: InstructionSet,
+    observers: Vec<Rc<RefCell<dyn Observer>>>,
+    cycle_detector: Option<CycleDetector>,
+    limits: Limits,
+    instruction_count: u64,
+    /// When the program stopped to wait for input, if there's a limit on how long it may wait.
+    waiting_since: Option<Instant>,
+    /// Whether input was buffered after the program had already waited too long for it.
+    late_input: bool,
+    /// A limit that the current instruction went over.
+    exceeded: Option<Limit>,
 }
 
-const MAX_INTCODE_SIZE: usize = 32 * 1024; // 32KB should be enough for anyone...
+/// The number of words of memory a program gets, which is also the longest program that can be
+/// loaded.
+pub const MAX_INTCODE_SIZE: usize = 32 * 1024; // 32KB should be enough for anyone...
 
 impl IntcodeProgram {
     pub fn set_pc(&mut self, new_pc: usize) {
@@ -23,11 +74,21 @@ impl IntcodeProgram {
     }
 
     pub fn init(memory: &Vec<i64>, inputs: Vec<i64>) -> IntcodeProgram {
+        Self::try_init(memory, inputs).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Like `init`, but returns an error instead of panicking if the program doesn't fit in
+    /// memory, e.g. for programs from untrusted sources.
+    pub fn try_init(memory: &[i64], inputs: Vec<i64>) -> Result<IntcodeProgram, IntcodeError> {
+        if memory.len() > MAX_INTCODE_SIZE {
+            return Err(IntcodeError::ProgramTooLarge { len: memory.len() });
+        }
+
         // allow MAX_INTCODE_SIZE memory space, initalized to 0
-        let mut program_memory = vec![0; MAX_INTCODE_SIZE];
-        program_memory[..memory.len()].clone_from_slice(&memory[..]);
+        let mut program_memory = Memory::with_size(MAX_INTCODE_SIZE);
+        program_memory.load_from(memory);
 
-        IntcodeProgram {
+        Ok(IntcodeProgram {
             memory: program_memory,
             input_buf: VecDeque::from(inputs),
             output_buf: VecDeque::new(),
@@ -35,7 +96,16 @@ impl IntcodeProgram {
             relative_base: 0,
             is_halted: false,
             is_awaiting_input: false,
-        }
+            opcodes: OpcodeRegistry::new(),
+            instruction_set: Default::default(),
+            observers: Vec::new(),
+            cycle_detector: None,
+            limits: Default::default(),
+            instruction_count: 0,
+            waiting_since: None,
+            late_input: false,
+            exceeded: None,
+        })
     }
 
     pub fn init_from(intcode_program: &str) -> IntcodeProgram {
@@ -43,34 +113,148 @@ impl IntcodeProgram {
         Self::init(&parsed, Default::default())
     }
 
+    /// Creates a copy of this program's current state that can be run independently, e.g. to
+    /// try out one branch of a search and then throw it away. Memory pages are shared between the
+    /// two programs until one of them writes to a page, so forking is cheap even when the program
+    /// has a lot of memory in use.
+    pub fn fork(&self) -> IntcodeProgram {
+        self.clone()
+    }
+
+    /// Restrict the program to the instructions of an earlier day's spec. Running an instruction
+    /// outside of it fails with `IntcodeError::UnsupportedInstruction`.
+    pub fn set_instruction_set(&mut self, instruction_set: InstructionSet) {
+        self.instruction_set = instruction_set;
+    }
+
     // TODO: Improve running w.r.t. halting, blocking for input, etc.
     pub fn run(&mut self) {
+        if let Err(e) = self.try_run() {
+            panic!("Aborting, {}", e)
+        }
+    }
+
+    /// Like `run`, but returns an error instead of panicking when the program reaches an
+    /// instruction it can't execute. The PC is left pointing at that instruction.
+    pub fn try_run(&mut self) -> Result<(), IntcodeError> {
+        self.run_until(false)
+    }
+
+    /// Runs until the program waits for input or halts, or also after the next jump instruction
+    /// (taken or not) if `pause_at_branch` is set.
+    pub(crate) fn run_until(&mut self, pause_at_branch: bool) -> Result<(), IntcodeError> {
         debug!("Resuming with PC: {}", self.pc);
+        self.check_input_wait()?;
 
         // instruction loop: continue until blocking to wait for input or the program halts
         loop {
-            let instruction = destructure_inst(self.memory[self.pc]);
-            debug!(
-                "PC({}), RB({}) :: {:?}",
-                self.pc, self.relative_base, instruction
-            );
-            match instruction {
-                Ok(inst) => {
-                    // TODO: Clean up the operation/instruction separation (or remove it...) and
-                    //   add back verbose debug logging for execution values.
-                    let operation = inst.as_operation(self.pc, self.relative_base, &self.memory);
-                    let result = self.apply(&operation);
-                    match result {
-                        IntcodeResult::AwaitingInput | IntcodeResult::Halted => return,
-                        IntcodeResult::ExecutedInstruction => {}
-                    }
+            let io = self.io_state();
+            let is_branch = pause_at_branch && self.is_at_branch();
+            match self.step()? {
+                IntcodeResult::AwaitingInput => {
+                    self.start_waiting();
+                    return Ok(());
+                }
+                IntcodeResult::Halted => return Ok(()),
+                IntcodeResult::ExecutedInstruction => {}
+            }
+
+            if self.cycle_detector.is_some() {
+                let did_io = io != self.io_state();
+                if self.check_for_cycle(did_io) {
+                    return Err(IntcodeError::Cycle { pc: self.pc });
                 }
-                Err(e) => panic!(format!("Aborting, invalid opcode: {:?}", e)),
+            }
+            if is_branch {
+                return Ok(());
             }
         }
     }
 
-    fn apply(&mut self, operation: &Operation) -> IntcodeResult {
+    /// Whether the instruction at the PC is a jump.
+    pub(crate) fn is_at_branch(&self) -> bool {
+        self.memory.contains(self.pc)
+            && destructure_inst(self.memory.peek(self.pc), &self.opcodes)
+                .is_ok_and(|inst| matches!(inst.op, Op::Jit | Op::Jif))
+    }
+
+    /// Executes the instruction at the PC.
+    fn step(&mut self) -> Result<IntcodeResult, IntcodeError> {
+        self.check_instruction_limit()?;
+        self.check_bounds(self.pc as i64)?;
+        let value = self.memory.get(self.pc);
+        let pc = self.pc;
+        self.notify(|o| o.on_fetch(pc, value));
+
+        let instruction = destructure_inst(value, &self.opcodes);
+        match instruction {
+            Ok(inst) => {
+                if let Err(feature) = self.instruction_set.check(&inst) {
+                    return Err(IntcodeError::UnsupportedInstruction {
+                        pc: self.pc,
+                        instruction: value,
+                        feature,
+                        instruction_set: self.instruction_set,
+                    });
+                }
+
+                self.check_bounds((self.pc + inst.op.num_parameters()) as i64)?;
+                // TODO: Clean up the operation/instruction separation (or remove it...)
+                let operation = inst.as_operation(self.pc, self.relative_base, &self.memory);
+                for slot in operation.slots.iter().flatten() {
+                    self.check_bounds(*slot)?;
+                }
+                self.check_operation_limits(&operation)?;
+                let result = self.apply(&operation);
+                if let Some(limit) = self.exceeded.take() {
+                    self.pc = pc;
+                    return Err(IntcodeError::LimitExceeded { pc, limit });
+                }
+                if let Ok(IntcodeResult::ExecutedInstruction) | Ok(IntcodeResult::Halted) = result {
+                    self.instruction_count += 1;
+                }
+                result
+            }
+            Err(error) => Err(IntcodeError::InvalidInstruction { pc: self.pc, error }),
+        }
+    }
+
+    fn check_bounds(&self, addr: i64) -> Result<(), IntcodeError> {
+        if addr < 0 || !self.memory.contains(addr as usize) {
+            return Err(IntcodeError::AddressOutOfBounds { pc: self.pc, addr });
+        }
+        Ok(())
+    }
+
+    fn jump_target(&self, slot: usize) -> Result<usize, IntcodeError> {
+        let target = self.load(slot);
+        self.check_bounds(target)?;
+        Ok(target as usize)
+    }
+
+    fn apply(&mut self, operation: &Operation) -> Result<IntcodeResult, IntcodeError> {
+        if let Op::Custom(opcode, _) = operation.op {
+            let slots: Vec<usize> = operation
+                .slots
+                .iter()
+                .map(|s| s.unwrap() as usize)
+                .collect();
+            return Ok(match self.apply_custom(*opcode, &slots)? {
+                CustomOpResult::Next | CustomOpResult::Jump(_) => {
+                    self.is_awaiting_input = false;
+                    IntcodeResult::ExecutedInstruction
+                }
+                CustomOpResult::AwaitInput => {
+                    self.is_awaiting_input = true;
+                    IntcodeResult::AwaitingInput
+                }
+                CustomOpResult::Halt => {
+                    self.halt();
+                    IntcodeResult::Halted
+                }
+            });
+        }
+
         // init with default that will throw out of bounds if we access the wrong input
         // TODO: do this more cleanly
         let mut r: [usize; 3] = [MAX_INTCODE_SIZE + 1; 3];
@@ -81,110 +265,89 @@ impl IntcodeProgram {
 
         match operation.op {
             Op::Add => {
-                self.store(r[2], self.memory[r[0]] + self.memory[r[1]]);
+                let sum = self.load(r[0]).checked_add(self.load(r[1]));
+                self.store(r[2], sum.ok_or(IntcodeError::Overflow { pc: self.pc })?);
                 self.inc_pc(4);
             }
             Op::Mul => {
-                let dst = r[2];
-                let r1 = self.memory[r[0]];
-                let r2 = self.memory[r[1]];
-
-                self.store(dst, r1 * r2);
+                let product = self.load(r[0]).checked_mul(self.load(r[1]));
+                self.store(r[2], product.ok_or(IntcodeError::Overflow { pc: self.pc })?);
                 self.inc_pc(4);
-
-                debug!("+ MUL {}, {} -> m[{}]", dst, r1, r2);
             }
             Op::Input => match self.consume_input() {
                 Some(value) => {
-                    let dst = r[0];
                     self.is_awaiting_input = false;
 
-                    self.store(dst, value);
+                    self.store(r[0], value);
                     self.inc_pc(2);
-
-                    debug!("<< INPUT {} -> m[{}]", value, dst);
                 }
                 None => {
                     debug!("Waiting for INPUT...");
                     self.is_awaiting_input = true;
-                    return IntcodeResult::AwaitingInput;
+                    return Ok(IntcodeResult::AwaitingInput);
                 }
             },
             Op::Output => {
-                let dst = r[0];
-                let output = self.memory[dst];
-
-                debug!(">> OUTPUT m[{}] >> {}", dst, output);
-
+                let output = self.load(r[0]);
                 self.buffer_output(output);
                 self.inc_pc(2);
             }
             Op::Jit => {
-                let pred = self.memory[r[0]];
-                let pc = if pred != 0 {
-                    let new_pc = self.memory[r[1]] as usize;
-                    debug!("+ JIT m[{}]...pass -> pc={}", r[0], new_pc);
-                    new_pc
+                self.pc = if self.load(r[0]) != 0 {
+                    self.jump_target(r[1])?
                 } else {
-                    debug!("+ JIT m[{}]...fail", r[0]);
                     self.pc + 3
                 };
-                self.pc = pc;
             }
             Op::Jif => {
-                let pred = self.memory[r[0]];
-                let pc = if pred == 0 {
-                    let new_pc = self.memory[r[1]] as usize;
-                    debug!("+ JIF m[{}]...pass -> pc={}", r[0], new_pc);
-                    new_pc
+                self.pc = if self.load(r[0]) == 0 {
+                    self.jump_target(r[1])?
                 } else {
-                    debug!("+ JIF m[{}]...fail", r[0]);
                     self.pc + 3
                 };
-                self.pc = pc;
             }
             Op::Lt => {
-                // TODO: Show addressing modes properly for debug output
-                debug!("+ LT m[{}], m[{}]", r[0], r[1]);
-                let r0 = self.memory[r[0]];
-                let r1 = self.memory[r[1]];
-                let dst = r[2];
-                let result = if r0 < r1 { 1 } else { 0 };
-
-                self.store(dst, result);
+                let result = if self.load(r[0]) < self.load(r[1]) {
+                    1
+                } else {
+                    0
+                };
+                self.store(r[2], result);
                 self.inc_pc(4);
-                debug!("++ LT {}, {} -> m[{}]", r0, r1, dst);
             }
             Op::Eq => {
-                let r0 = self.memory[r[0]];
-                let r1 = self.memory[r[1]];
-                let dst = r[2];
-                let result = if r0 == r1 { 1 } else { 0 };
-
-                self.store(dst, result);
+                let result = if self.load(r[0]) == self.load(r[1]) {
+                    1
+                } else {
+                    0
+                };
+                self.store(r[2], result);
                 self.inc_pc(4);
-                debug!("+ LT {}, {} -> m[{}]", r0, r1, dst);
             }
             Op::RelBaseOffset => {
-                self.relative_base = self.relative_base + self.memory[r[0]];
+                self.relative_base = self
+                    .relative_base
+                    .checked_add(self.load(r[0]))
+                    .ok_or(IntcodeError::Overflow { pc: self.pc })?;
                 self.inc_pc(2);
-                debug!("+ SETRB {}", self.relative_base);
             }
             Op::Halt => {
-                self.is_halted = true;
-                debug!("+ HALT");
-                return IntcodeResult::Halted;
+                self.halt();
+                return Ok(IntcodeResult::Halted);
             }
+            Op::Custom(..) => unreachable!(),
         };
 
-        IntcodeResult::ExecutedInstruction
+        Ok(IntcodeResult::ExecutedInstruction)
     }
 
+    /// The value at `mem_i`, peeking at a mapped device rather than loading from it.
     pub fn mem_value(&self, mem_i: usize) -> i64 {
-        self.memory[mem_i]
+        self.memory.peek(mem_i)
     }
 
     pub fn buffer_input(&mut self, input: i64) {
+        self.stop_waiting();
         self.input_buf.push_back(input)
     }
 
@@ -204,16 +367,59 @@ impl IntcodeProgram {
         self.pc += inc;
     }
 
+    /// Attaches an observer that's called back as the program runs. Forks of this program share
+    /// the same observers.
+    pub fn add_observer(&mut self, observer: Rc<RefCell<dyn Observer>>) {
+        self.observers.push(observer);
+    }
+
+    pub fn clear_observers(&mut self) {
+        self.observers.clear();
+    }
+
+    fn notify<F: Fn(&mut dyn Observer)>(&self, f: F) {
+        // avoid any cost when there are no observers
+        if self.observers.is_empty() {
+            return;
+        }
+        for observer in self.observers.iter() {
+            f(&mut *observer.borrow_mut());
+        }
+    }
+
     fn consume_input(&mut self) -> Option<i64> {
-        self.input_buf.pop_front()
+        let input = self.input_buf.pop_front();
+        if let Some(value) = input {
+            self.notify(|o| o.on_input(value));
+        }
+        input
     }
 
     fn buffer_output(&mut self, output: i64) {
+        if !self.may_output() {
+            return;
+        }
+        self.notify(|o| o.on_output(output));
         self.output_buf.push_back(output)
     }
 
+    fn halt(&mut self) {
+        self.is_halted = true;
+        self.notify(|o| o.on_halt());
+    }
+
+    fn load(&self, location: usize) -> i64 {
+        let value = self.memory.get(location);
+        self.notify(|o| o.on_read(location, value));
+        value
+    }
+
     fn store(&mut self, location: usize, value: i64) {
-        self.memory[location] = value
+        if !self.may_store(location) {
+            return;
+        }
+        self.notify(|o| o.on_write(location, value));
+        self.memory.set(location, value)
     }
 }
 
@@ -224,7 +430,10 @@ enum IntcodeResult {
     ExecutedInstruction,
 }
 
-fn destructure_inst(inst: i64) -> std::result::Result<Instruction, InvalidOpCodeError> {
+fn destructure_inst(
+    inst: i64,
+    opcodes: &OpcodeRegistry,
+) -> std::result::Result<Instruction, InvalidOpCodeError> {
     let mut digits = digits(inst);
     let mut addr_modes: Vec<AddressingMode> = Vec::new();
 
@@ -232,7 +441,7 @@ fn destructure_inst(inst: i64) -> std::result::Result<Instruction, InvalidOpCode
     let opcode_1 = digits.pop_back().unwrap_or_default();
     let opcode: i8 = opcode_1 * 10 + opcode_0;
 
-    let op = Op::from_opcode(opcode)?;
+    let op = Op::from_opcode(opcode, opcodes)?;
     for _i in 0..op.num_parameters() {
         addr_modes.push(AddressingMode::from(digits.pop_back())?);
     }
@@ -243,7 +452,7 @@ fn destructure_inst(inst: i64) -> std::result::Result<Instruction, InvalidOpCode
 fn get_parameter_mem_slot(
     pc: usize,
     relative_base: i64,
-    memory: &Vec<i64>,
+    memory: &Memory,
     parm_index: usize, // 0-indexed
     inst: &Instruction,
 ) -> Option<i64> {
@@ -254,8 +463,9 @@ fn get_parameter_mem_slot(
         let mode = inst.addr_modes.get(parm_index);
         mode.map(|m| match m {
             AddressingMode::Immediate => parm_slot as i64,
-            AddressingMode::Position => memory[parm_slot],
-            AddressingMode::Relative => memory[parm_slot] + relative_base,
+            AddressingMode::Position => memory.get(parm_slot),
+            // saturates so that it fails the bounds check rather than overflowing
+            AddressingMode::Relative => memory.get(parm_slot).saturating_add(relative_base),
         })
     }
 }
@@ -272,9 +482,9 @@ struct Operation<'a> {
 }
 
 impl Instruction {
-    fn as_operation(&self, pc: usize, relative_base: i64, memory: &Vec<i64>) -> Operation {
+    fn as_operation(&self, pc: usize, relative_base: i64, memory: &Memory) -> Operation<'_> {
         let mut slots = Vec::new();
-        for i in 0..3 {
+        for i in 0..self.op.num_parameters() {
             slots.push(get_parameter_mem_slot(pc, relative_base, memory, i, &self));
         }
         Operation {
@@ -296,10 +506,11 @@ enum Op {
     Eq,
     RelBaseOffset,
     Halt,
+    Custom(i8, usize),
 }
 
 impl Op {
-    fn from_opcode(opcode: i8) -> Result<Op, InvalidOpCodeError> {
+    fn from_opcode(opcode: i8, opcodes: &OpcodeRegistry) -> Result<Op, InvalidOpCodeError> {
         let op = match opcode {
             1 => Some(Op::Add),
             2 => Some(Op::Mul),
@@ -311,19 +522,38 @@ impl Op {
             8 => Some(Op::Eq),
             9 => Some(Op::RelBaseOffset),
             99 => Some(Op::Halt),
-            _ => None,
+            _ => opcodes
+                .num_parameters(opcode)
+                .map(|num_parameters| Op::Custom(opcode, num_parameters)),
         };
         op.ok_or_else(|| InvalidOpCodeError {
             message: format!("Invalid opcode: {}", opcode),
         })
     }
 
+    fn name(&self) -> &'static str {
+        match self {
+            Op::Add => "add",
+            Op::Mul => "multiply",
+            Op::Input => "input",
+            Op::Output => "output",
+            Op::Jit => "jump-if-true",
+            Op::Jif => "jump-if-false",
+            Op::Lt => "less than",
+            Op::Eq => "equals",
+            Op::RelBaseOffset => "relative base offset",
+            Op::Halt => "halt",
+            Op::Custom(..) => "custom opcode",
+        }
+    }
+
     fn num_parameters(&self) -> usize {
         match self {
             Op::Add | Op::Mul | Op::Eq | Op::Lt => 3,
             Op::Jit | Op::Jif => 2,
             Op::Input | Op::Output | Op::RelBaseOffset => 1,
             Op::Halt => 0,
+            Op::Custom(_, num_parameters) => *num_parameters,
         }
     }
 }
@@ -359,12 +589,97 @@ impl Display for InvalidOpCodeError {
     }
 }
 
+#[derive(Debug)]
+pub enum IntcodeError {
+    /// The value at `pc` isn't a valid instruction.
+    InvalidInstruction {
+        pc: usize,
+        error: InvalidOpCodeError,
+    },
+    /// The instruction at `pc` uses something that isn't part of the program's instruction set.
+    UnsupportedInstruction {
+        pc: usize,
+        instruction: i64,
+        feature: &'static str,
+        instruction_set: InstructionSet,
+    },
+    /// The instruction at `pc` (or the PC itself) refers to an address outside of memory.
+    AddressOutOfBounds { pc: usize, addr: i64 },
+    /// The result of an addition, multiplication or relative base adjustment at `pc` doesn't fit
+    /// in an i64.
+    Overflow { pc: usize },
+    /// The program got back to a state it was already in without any I/O since, so it would loop
+    /// forever. Only detected with `set_cycle_detection`.
+    Cycle { pc: usize },
+    /// The instruction at `pc` went over one of the limits set with `set_limits`.
+    LimitExceeded { pc: usize, limit: Limit },
+    /// The program stopped at `pc` partway through a chunk of `chunk_size` outputs.
+    IncompleteOutput {
+        pc: usize,
+        outputs: Vec<i64>,
+        chunk_size: usize,
+    },
+    /// The program is `len` words long, more than the `MAX_INTCODE_SIZE` words of memory.
+    ProgramTooLarge { len: usize },
+}
+
+impl Display for IntcodeError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), std::fmt::Error> {
+        match self {
+            IntcodeError::InvalidInstruction { pc, error } => {
+                write!(f, "PC {}: {}", pc, error.message)
+            }
+            IntcodeError::UnsupportedInstruction {
+                pc,
+                instruction,
+                feature,
+                instruction_set,
+            } => write!(
+                f,
+                "PC {}: {} (instruction {}) is not part of the {} instruction set",
+                pc, feature, instruction, instruction_set
+            ),
+            IntcodeError::AddressOutOfBounds { pc, addr } => {
+                write!(f, "PC {}: address {} is outside of memory", pc, addr)
+            }
+            IntcodeError::Overflow { pc } => write!(f, "PC {}: arithmetic overflow", pc),
+            IntcodeError::Cycle { pc } => write!(f, "PC {}: the program is stuck in a loop", pc),
+            IntcodeError::LimitExceeded { pc, limit } => write!(f, "PC {}: {}", pc, limit),
+            IntcodeError::IncompleteOutput {
+                pc,
+                outputs,
+                chunk_size,
+            } => write!(
+                f,
+                "PC {}: stopped with an incomplete chunk of outputs {:?}, expected {} values",
+                pc, outputs, chunk_size
+            ),
+            IntcodeError::ProgramTooLarge { len } => write!(
+                f,
+                "the program is {} words long, but memory only holds {}",
+                len, MAX_INTCODE_SIZE
+            ),
+        }
+    }
+}
+
+impl std::error::Error for IntcodeError {}
+
+/// Parses an intcode program, panicking if it can't be parsed. See `loader::load_intcode` for the
+/// accepted format.
 pub fn parse_intcode_input(input: &str) -> Vec<i64> {
-    input
-        .trim()
-        .split(',')
-        .map(|it| it.parse::<i64>().expect("Can't parse int"))
-        .collect()
+    try_parse_intcode_input(input).unwrap_or_else(|e| panic!("{}", e))
+}
+
+/// Parses an intcode program, with an error message for a bad puzzle input.
+pub fn try_parse_intcode_input(input: &str) -> Result<Vec<i64>, String> {
+    load_intcode(input).map_err(|e| format!("Can't parse intcode: {}", e))
+}
+
+/// Checks that a puzzle input is an intcode program, so a day can report a bad input instead of
+/// panicking on it.
+pub fn check_intcode_input(input: &str) -> Result<(), String> {
+    try_parse_intcode_input(input).map(|_| ())
 }
 
 fn digits(num: i64) -> VecDeque<i8> {
@@ -379,7 +694,9 @@ fn digits(num: i64) -> VecDeque<i8> {
 
 #[cfg(test)]
 mod tests {
-    use crate::{destructure_inst, AddressingMode, IntcodeProgram, Op};
+    use crate::{
+        destructure_inst, AddressingMode, IntcodeProgram, Op, OpcodeRegistry, MAX_INTCODE_SIZE,
+    };
 
     #[test]
     fn test_parse_relative_mode() {
@@ -435,7 +752,7 @@ mod tests {
     fn test_run_rel_base_ex3() {
         let intcode = "104,1125899906842624,99";
         let mut program = IntcodeProgram::init_from(intcode);
-        let expected = [program.memory[1]];
+        let expected = [program.mem_value(1)];
         program.run();
         assert_eq!(program.output_buf, expected)
     }
@@ -449,15 +766,36 @@ mod tests {
         let rel_base = 2000;
         let mut program = IntcodeProgram::init_from("109,19,204,-34,99");
         program.relative_base = rel_base;
-        program.memory[1985] = 1111;
+        program.memory.set(1985, 1111);
         program.run();
         assert_eq!(program.consume_output().unwrap(), 1111);
     }
 
+    #[test]
+    fn test_fork_runs_independently() {
+        // read a value, add it to m[9], output m[9]
+        let mut parent = IntcodeProgram::init_from("3,10,1,9,10,9,4,9,99,100,0");
+        parent.run();
+        assert!(parent.is_awaiting_input());
+
+        let mut child = parent.fork();
+        child.buffer_input(5);
+        child.run();
+        assert_eq!(child.consume_output(), Some(105));
+        assert_eq!(child.mem_value(9), 105);
+
+        assert!(parent.is_awaiting_input());
+        assert_eq!(parent.mem_value(9), 100);
+
+        parent.buffer_input(7);
+        parent.run();
+        assert_eq!(parent.consume_output(), Some(107));
+    }
+
     #[test]
     fn test_decode_relative_address_mode_for_input_store() {
         let instr = 203;
-        let x = destructure_inst(instr);
+        let x = destructure_inst(instr, &OpcodeRegistry::new());
         assert!(x.is_ok());
 
         let result = x.unwrap();
@@ -467,4 +805,16 @@ mod tests {
         assert!(addr_mode.is_some());
         assert_eq!(*addr_mode.unwrap(), AddressingMode::Relative);
     }
+
+    #[test]
+    fn test_program_too_large() {
+        assert!(IntcodeProgram::try_init(&[99; MAX_INTCODE_SIZE], Vec::new()).is_ok());
+        assert_eq!(
+            IntcodeProgram::try_init(&[99; MAX_INTCODE_SIZE + 1], Vec::new())
+                .err()
+                .unwrap()
+                .to_string(),
+            "the program is 32769 words long, but memory only holds 32768"
+        );
+    }
 }
@@ -0,0 +1,295 @@
+//! Limits on what a program can use, for running programs that can't be trusted. A program that
+//! goes over a limit stops with `IntcodeError::LimitExceeded`, with the PC left pointing at the
+//! instruction that went over it. That instruction has no effect, so the program can carry on
+//! from there if the limits are raised, except for custom opcodes, whose handlers have already
+//! done everything up to the store or output that went over.
+
+use crate::{IntcodeError, IntcodeProgram, Op, Operation};
+use std::fmt::{Display, Formatter};
+use std::time::{Duration, Instant};
+
+/// What a program may use. Nothing is limited by default.
+#[derive(Debug, Copy, Clone, Default, PartialEq)]
+pub struct Limits {
+    /// The most instructions the program may execute, over every call to `run`.
+    pub max_instructions: Option<u64>,
+    /// The most words of memory the program may use. Memory is allocated in pages of 1024 words
+    /// when they're first written, so this counts the whole page of every word written, including
+    /// the program itself.
+    pub max_memory: Option<usize>,
+    /// The most outputs that may be buffered at once, i.e. output but not consumed yet.
+    pub max_outputs: Option<usize>,
+    /// The longest the program may wait for input, from when it stops to wait until input is
+    /// buffered. This is checked when the program is run again, see
+    /// `IntcodeProgram::input_deadline`.
+    pub max_input_wait: Option<Duration>,
+}
+
+/// The limit that a program went over.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum Limit {
+    Instructions(u64),
+    Memory(usize),
+    Outputs(usize),
+    InputWait(Duration),
+}
+
+impl Display for Limit {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), std::fmt::Error> {
+        match self {
+            Limit::Instructions(max) => write!(f, "exceeded the limit of {} instructions", max),
+            Limit::Memory(max) => write!(f, "exceeded the limit of {} words of memory", max),
+            Limit::Outputs(max) => write!(f, "exceeded the limit of {} buffered outputs", max),
+            Limit::InputWait(max) => write!(f, "waited longer than {:?} for input", max),
+        }
+    }
+}
+
+impl IntcodeProgram {
+    pub fn set_limits(&mut self, limits: Limits) {
+        self.limits = limits;
+    }
+
+    /// The number of instructions the program has executed, counting from when it was loaded.
+    pub fn instruction_count(&self) -> u64 {
+        self.instruction_count
+    }
+
+    /// When the program will have waited too long for input, if it's waiting for input and there's a
+    /// limit on how long it may wait, e.g. to stop waiting for more input before then.
+    pub fn input_deadline(&self) -> Option<Instant> {
+        match (self.waiting_since, self.limits.max_input_wait) {
+            (Some(since), Some(max)) => Some(since + max),
+            _ => None,
+        }
+    }
+
+    pub(crate) fn check_instruction_limit(&self) -> Result<(), IntcodeError> {
+        match self.limits.max_instructions {
+            Some(max) if self.instruction_count >= max => {
+                Err(self.limit_exceeded(Limit::Instructions(max)))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Called when the program is run. Fails if input was buffered too late, or if there's still
+    /// no input and the program has been waiting for too long.
+    pub(crate) fn check_input_wait(&self) -> Result<(), IntcodeError> {
+        if let Some(max) = self.limits.max_input_wait {
+            let still_waiting_too_long = self
+                .waiting_since
+                .is_some_and(|since| since.elapsed() > max);
+            if self.late_input || still_waiting_too_long {
+                return Err(self.limit_exceeded(Limit::InputWait(max)));
+            }
+        }
+        Ok(())
+    }
+
+    /// Called when the program stops to wait for input.
+    pub(crate) fn start_waiting(&mut self) {
+        if self.limits.max_input_wait.is_some() && self.waiting_since.is_none() {
+            self.waiting_since = Some(Instant::now());
+        }
+    }
+
+    /// Called when input is buffered, to stop the clock.
+    pub(crate) fn stop_waiting(&mut self) {
+        if let (Some(since), Some(max)) = (self.waiting_since.take(), self.limits.max_input_wait) {
+            if since.elapsed() > max {
+                self.late_input = true;
+            }
+        }
+    }
+
+    /// Fails if running `operation` would go over the memory or output limit, before it has any
+    /// effect. Custom opcodes can't be checked ahead like this, see `may_store`.
+    pub(crate) fn check_operation_limits(&self, operation: &Operation) -> Result<(), IntcodeError> {
+        let limit = match operation.op {
+            Op::Add | Op::Mul | Op::Lt | Op::Eq => self.memory_limit(operation.slots[2]),
+            // without input the instruction just waits, and doesn't store anything
+            Op::Input if !self.input_buf.is_empty() => self.memory_limit(operation.slots[0]),
+            Op::Output => self.output_limit(),
+            _ => None,
+        };
+        match limit {
+            Some(limit) => Err(self.limit_exceeded(limit)),
+            None => Ok(()),
+        }
+    }
+
+    /// Whether a store to `addr` from a custom opcode is allowed. If it's not, the store is skipped
+    /// and the instruction fails once the handler returns.
+    pub(crate) fn may_store(&mut self, addr: usize) -> bool {
+        match self.memory_limit(Some(addr as i64)) {
+            Some(limit) => {
+                self.exceeded.get_or_insert(limit);
+                false
+            }
+            None => true,
+        }
+    }
+
+    /// Whether another output from a custom opcode is allowed, like `may_store`.
+    pub(crate) fn may_output(&mut self) -> bool {
+        match self.output_limit() {
+            Some(limit) => {
+                self.exceeded.get_or_insert(limit);
+                false
+            }
+            None => true,
+        }
+    }
+
+    fn memory_limit(&self, addr: Option<i64>) -> Option<Limit> {
+        let addr = addr? as usize;
+        match self.limits.max_memory {
+            Some(max) if self.memory.allocated() + self.memory.allocation_for(addr) > max => {
+                Some(Limit::Memory(max))
+            }
+            _ => None,
+        }
+    }
+
+    fn output_limit(&self) -> Option<Limit> {
+        match self.limits.max_outputs {
+            Some(max) if self.output_buf.len() >= max => Some(Limit::Outputs(max)),
+            _ => None,
+        }
+    }
+
+    fn limit_exceeded(&self, limit: Limit) -> IntcodeError {
+        IntcodeError::LimitExceeded { pc: self.pc, limit }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::limits::{Limit, Limits};
+    use crate::transcript::Recorder;
+    use crate::{IntcodeError, IntcodeProgram};
+    use std::cell::RefCell;
+    use std::rc::Rc;
+    use std::thread::sleep;
+    use std::time::Duration;
+
+    fn limit_exceeded(program: &mut IntcodeProgram) -> (usize, Limit) {
+        match program.try_run() {
+            Err(IntcodeError::LimitExceeded { pc, limit }) => (pc, limit),
+            result => panic!("Expected to exceed a limit, got {:?}", result),
+        }
+    }
+
+    #[test]
+    fn test_instruction_limit() {
+        // counts down from 5
+        let countdown = "1001,10,-1,10,1005,10,0,99,0,0,5";
+        let mut program = IntcodeProgram::init_from(countdown);
+        program.run();
+        assert_eq!(program.instruction_count(), 11);
+
+        let mut program = IntcodeProgram::init_from(countdown);
+        program.set_limits(Limits {
+            max_instructions: Some(11),
+            ..Default::default()
+        });
+        program.run();
+        assert!(program.is_halted());
+
+        let mut program = IntcodeProgram::init_from(countdown);
+        program.set_limits(Limits {
+            max_instructions: Some(10),
+            ..Default::default()
+        });
+        assert_eq!(limit_exceeded(&mut program), (7, Limit::Instructions(10)));
+        assert_eq!(
+            program.try_run().unwrap_err().to_string(),
+            "PC 7: exceeded the limit of 10 instructions"
+        );
+    }
+
+    #[test]
+    fn test_memory_limit() {
+        // writes to the next page
+        let mut program = IntcodeProgram::init_from("1101,1,2,2000,99");
+        program.set_limits(Limits {
+            max_memory: Some(1024),
+            ..Default::default()
+        });
+        assert_eq!(limit_exceeded(&mut program), (0, Limit::Memory(1024)));
+        assert_eq!(program.mem_value(2000), 0);
+
+        // an input that would be stored over the limit is left for when the program carries on
+        let recorder = Rc::new(RefCell::new(Recorder::default()));
+        let mut program = IntcodeProgram::init(&vec![3, 2000, 99], vec![5]);
+        program.add_observer(recorder.clone());
+        program.set_limits(Limits {
+            max_memory: Some(1024),
+            ..Default::default()
+        });
+        assert_eq!(limit_exceeded(&mut program), (0, Limit::Memory(1024)));
+        assert!(recorder.borrow().transcript().events.is_empty());
+        program.set_limits(Default::default());
+        program.run();
+        assert_eq!(program.mem_value(2000), 5);
+        assert_eq!(recorder.borrow().transcript().to_string(), "in 5\n");
+
+        // writing to a page that's already in use is fine
+        let mut program = IntcodeProgram::init_from("1101,1,2,20,99");
+        program.set_limits(Limits {
+            max_memory: Some(1024),
+            ..Default::default()
+        });
+        program.run();
+        assert_eq!(program.mem_value(20), 3);
+    }
+
+    #[test]
+    fn test_output_limit() {
+        // outputs 1 forever
+        let mut program = IntcodeProgram::init_from("104,1,1105,1,0");
+        program.set_limits(Limits {
+            max_outputs: Some(3),
+            ..Default::default()
+        });
+        assert_eq!(limit_exceeded(&mut program), (0, Limit::Outputs(3)));
+        assert_eq!(program.consume_output(), Some(1));
+
+        // consuming outputs makes room for more
+        assert_eq!(limit_exceeded(&mut program), (0, Limit::Outputs(3)));
+        assert_eq!(program.instruction_count(), 8);
+    }
+
+    #[test]
+    fn test_input_wait_limit() {
+        let echo = "3,7,4,7,1105,1,0,0";
+        let limits = Limits {
+            max_input_wait: Some(Duration::from_millis(20)),
+            ..Default::default()
+        };
+
+        let mut program = IntcodeProgram::init_from(echo);
+        program.set_limits(limits);
+        assert_eq!(program.input_deadline(), None);
+        program.run();
+        assert!(program.is_awaiting_input());
+        assert!(program.input_deadline().is_some());
+        program.buffer_input(1);
+        program.run();
+        assert_eq!(program.consume_output(), Some(1));
+
+        // it's how long until input is buffered that counts, not until the program is run again
+        program.buffer_input(2);
+        sleep(Duration::from_millis(40));
+        program.run();
+        assert_eq!(program.consume_output(), Some(2));
+
+        sleep(Duration::from_millis(40));
+        program.buffer_input(3);
+        assert_eq!(
+            limit_exceeded(&mut program),
+            (0, Limit::InputWait(Duration::from_millis(20)))
+        );
+    }
+}
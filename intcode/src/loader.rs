@@ -0,0 +1,138 @@
+use std::fmt::{Display, Formatter};
+
+/// Where and why an intcode source couldn't be loaded. Lines and columns start at 1.
+#[derive(Debug, PartialEq)]
+pub struct ParseError {
+    pub line: usize,
+    pub column: usize,
+    pub token: String,
+}
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), std::fmt::Error> {
+        if self.token.is_empty() {
+            write!(f, "line {}, column {}: empty item", self.line, self.column)
+        } else {
+            write!(
+                f,
+                "line {}, column {}: '{}' is not an integer",
+                self.line, self.column, self.token
+            )
+        }
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+/// Loads an intcode program from source text. Values may be separated by commas, newlines or
+/// both, and anything after a `#` on a line is a comment. A trailing comma at the end of a line
+/// is allowed, but an empty item anywhere else is an error.
+pub fn load_intcode(source: &str) -> Result<Vec<i64>, ParseError> {
+    let mut memory = Vec::new();
+
+    for (line_i, line) in source.lines().enumerate() {
+        let code = match line.find('#') {
+            Some(comment_start) => &line[..comment_start],
+            None => line,
+        };
+
+        let items: Vec<&str> = code.split(',').collect();
+        let mut column = 1;
+        for (item_i, item) in items.iter().enumerate() {
+            let token = item.trim();
+            let token_column = column + (item.len() - item.trim_start().len());
+            column += item.len() + 1;
+
+            if token.is_empty() {
+                if item_i == items.len() - 1 {
+                    // blank line, or trailing comma
+                    continue;
+                }
+            } else if let Ok(value) = token.parse::<i64>() {
+                memory.push(value);
+                continue;
+            }
+
+            return Err(ParseError {
+                line: line_i + 1,
+                column: token_column,
+                token: token.to_string(),
+            });
+        }
+    }
+
+    Ok(memory)
+}
+
+/// Formats a program the way puzzle inputs are given, as a single line of comma separated values.
+pub fn to_canonical(memory: &[i64]) -> String {
+    memory
+        .iter()
+        .map(|value| value.to_string())
+        .collect::<Vec<String>>()
+        .join(",")
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::loader::{load_intcode, to_canonical, ParseError};
+
+    #[test]
+    fn test_load_canonical() {
+        assert_eq!(load_intcode("1,0,0,0,99\n"), Ok(vec![1, 0, 0, 0, 99]));
+        assert_eq!(load_intcode("104,-1,99"), Ok(vec![104, -1, 99]));
+    }
+
+    #[test]
+    fn test_load_separators_and_comments() {
+        let source = "# add two numbers
+1, 9, 10, 3,  # m[3] = m[9] + m[10]
+2,3,11,0
+99
+
+30  # data
+40
+50,
+";
+        assert_eq!(
+            load_intcode(source),
+            Ok(vec![1, 9, 10, 3, 2, 3, 11, 0, 99, 30, 40, 50])
+        );
+    }
+
+    #[test]
+    fn test_load_one_per_line_matches_comma_separated() {
+        let lines = load_intcode(include_str!("../../day13/day13-lines.txt")).unwrap();
+        let commas = load_intcode(include_str!("../../day13/day13.txt")).unwrap();
+        assert_eq!(lines, commas);
+    }
+
+    #[test]
+    fn test_load_errors_have_positions() {
+        assert_eq!(
+            load_intcode("1,2,3\n4, five ,6"),
+            Err(ParseError {
+                line: 2,
+                column: 4,
+                token: "five".to_string(),
+            })
+        );
+        assert_eq!(
+            load_intcode("1,,3").unwrap_err().to_string(),
+            "line 1, column 3: empty item"
+        );
+        assert_eq!(
+            load_intcode("1,2 3 # comment, 4").unwrap_err().to_string(),
+            "line 1, column 3: '2 3' is not an integer"
+        );
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let source = "3,0,  # input\n4,0\n99,\n";
+        let memory = load_intcode(source).unwrap();
+        let canonical = to_canonical(&memory);
+        assert_eq!(canonical, "3,0,4,0,99");
+        assert_eq!(load_intcode(&canonical), Ok(memory));
+    }
+}
@@ -0,0 +1,73 @@
+use crate::memory::cell_hash;
+use crate::{IntcodeError, IntcodeProgram, MAX_INTCODE_SIZE};
+
+/// The interface shared by every way of running an intcode program, so callers and tests don't
+/// depend on a particular backend.
+pub trait IntcodeMachine {
+    /// Runs until the program halts or waits for input, or returns an error if it reaches an
+    /// instruction it can't execute.
+    fn try_run(&mut self) -> Result<(), IntcodeError>;
+
+    /// Like `try_run`, but also stops after executing a jump instruction, whether or not it jumped,
+    /// so that machines can be compared at every branch.
+    fn try_run_to_branch(&mut self) -> Result<(), IntcodeError>;
+
+    fn run(&mut self) {
+        if let Err(e) = self.try_run() {
+            panic!("Aborting, {}", e)
+        }
+    }
+
+    fn buffer_input(&mut self, input: i64);
+    fn consume_output(&mut self) -> Option<i64>;
+    fn mem_value(&self, addr: usize) -> i64;
+
+    /// A hash of every value in memory, equal for machines whose memory is equal. Backends that
+    /// keep one up to date as they store should override this, since the default reads every
+    /// address.
+    fn memory_hash(&self) -> u64 {
+        (0..MAX_INTCODE_SIZE).fold(0, |hash, addr| hash ^ cell_hash(addr, self.mem_value(addr)))
+    }
+
+    fn pc(&self) -> usize;
+    fn is_awaiting_input(&self) -> bool;
+    fn is_halted(&self) -> bool;
+}
+
+impl IntcodeMachine for IntcodeProgram {
+    fn try_run(&mut self) -> Result<(), IntcodeError> {
+        IntcodeProgram::try_run(self)
+    }
+
+    fn try_run_to_branch(&mut self) -> Result<(), IntcodeError> {
+        self.run_until(true)
+    }
+
+    fn buffer_input(&mut self, input: i64) {
+        IntcodeProgram::buffer_input(self, input)
+    }
+
+    fn consume_output(&mut self) -> Option<i64> {
+        IntcodeProgram::consume_output(self)
+    }
+
+    fn mem_value(&self, addr: usize) -> i64 {
+        IntcodeProgram::mem_value(self, addr)
+    }
+
+    fn memory_hash(&self) -> u64 {
+        self.memory.hash()
+    }
+
+    fn pc(&self) -> usize {
+        self.pc
+    }
+
+    fn is_awaiting_input(&self) -> bool {
+        IntcodeProgram::is_awaiting_input(self)
+    }
+
+    fn is_halted(&self) -> bool {
+        IntcodeProgram::is_halted(self)
+    }
+}
@@ -0,0 +1,673 @@
+extern crate intcode;
+
+use intcode::image::{is_image, load_image};
+use intcode::limits::Limits;
+use intcode::loader::load_intcode;
+use intcode::observer::DebugLog;
+use intcode::strings::{printed_strings, scan_strings, FoundString};
+use intcode::symbols::SymbolTable;
+use intcode::transcript::{replay, Recorder, Transcript};
+use intcode::{IntcodeProgram, MAX_INTCODE_SIZE};
+use std::cell::RefCell;
+use std::io::{BufRead, Write};
+use std::process::exit;
+use std::rc::Rc;
+use std::time::Duration;
+
+const USAGE: &str = "Usage: intcode <program> [options]
+
+The program can be comma separated text or a binary intcode image.
+
+Options:
+  --input 1,2,3     buffer the given numbers as input
+  --ascii TEXT      buffer TEXT as ASCII input (add a newline with \\n)
+  --interactive     read more input from stdin whenever the program runs out
+  --output MODE     print outputs as `numbers` (the default) or `ascii`
+  --set ADDR=VALUE  store VALUE at ADDR before running
+  --record FILE     save a transcript of the session's inputs and outputs to FILE
+  --replay FILE     replay a recorded transcript before taking any other input, stopping if the
+                    program's outputs don't match
+  --max-instructions N, --max-memory WORDS, --max-outputs N, --max-input-wait SECONDS
+                    stop with an error if the program goes over one of these limits
+  --strings         list the strings stored in the program, and the ones it prints when run on
+                    the given input (for up to 10000000 instructions, unless limited), instead of
+                    running it as usual
+  --trace           print every instruction, memory access, input and output to stderr
+  --symbols FILE    name addresses in the trace from a symbol file, instead of from the symbols
+                    saved in an image
+
+Exit status: 0 when the program halts, 1 on an error, 2 when it's waiting for input that isn't
+coming.";
+
+const EXIT_HALTED: i32 = 0;
+const EXIT_ERROR: i32 = 1;
+const EXIT_STARVED: i32 = 2;
+
+fn main() {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let options = match Options::parse(&args) {
+        Ok(options) => options,
+        Err(e) => {
+            eprintln!("{}\n\n{}", e, USAGE);
+            exit(EXIT_ERROR);
+        }
+    };
+
+    let program = match std::fs::read(&options.program_path) {
+        Ok(program) => program,
+        Err(e) => {
+            eprintln!("Can't read '{}': {}", options.program_path, e);
+            exit(EXIT_ERROR);
+        }
+    };
+
+    let transcript = options.replay_path.as_ref().map(|path| {
+        let text = std::fs::read_to_string(path).unwrap_or_else(|e| {
+            eprintln!("Can't read '{}': {}", path, e);
+            exit(EXIT_ERROR);
+        });
+        Transcript::parse(&text).unwrap_or_else(|e| {
+            eprintln!("Can't parse transcript '{}': {}", path, e);
+            exit(EXIT_ERROR);
+        })
+    });
+    let symbols = options.symbols_path.as_ref().map(|path| {
+        let text = std::fs::read_to_string(path).unwrap_or_else(|e| {
+            eprintln!("Can't read '{}': {}", path, e);
+            exit(EXIT_ERROR);
+        });
+        SymbolTable::parse(&text).unwrap_or_else(|e| {
+            eprintln!("Can't parse symbols '{}': {}", path, e);
+            exit(EXIT_ERROR);
+        })
+    });
+    let recorder = Rc::new(RefCell::new(Recorder::default()));
+
+    let stdin = std::io::stdin();
+    let stdout = std::io::stdout();
+    let session = Session {
+        replay: transcript.as_ref(),
+        recorder: options.record_path.as_ref().map(|_| recorder.clone()),
+        trace: if options.trace {
+            Some(Box::new(std::io::stderr()))
+        } else {
+            None
+        },
+        symbols: symbols.as_ref(),
+    };
+    let status = run(
+        &options,
+        &program,
+        session,
+        &mut stdin.lock(),
+        &mut stdout.lock(),
+    );
+
+    if let Some(path) = &options.record_path {
+        let transcript = recorder.borrow().transcript().to_string();
+        if let Err(e) = std::fs::write(path, transcript) {
+            eprintln!("Can't write '{}': {}", path, e);
+            exit(EXIT_ERROR);
+        }
+    }
+    exit(status);
+}
+
+#[derive(Debug, PartialEq)]
+enum OutputMode {
+    Numbers,
+    Ascii,
+}
+
+#[derive(Debug, PartialEq)]
+struct Options {
+    program_path: String,
+    inputs: Vec<i64>,
+    interactive: bool,
+    output_mode: OutputMode,
+    patches: Vec<(usize, i64)>,
+    record_path: Option<String>,
+    replay_path: Option<String>,
+    list_strings: bool,
+    trace: bool,
+    symbols_path: Option<String>,
+    limits: Limits,
+}
+
+/// A transcript to replay before running, where to record the session to, and where to trace it
+/// to with which symbols.
+#[derive(Default)]
+struct Session<'a> {
+    replay: Option<&'a Transcript>,
+    recorder: Option<Rc<RefCell<Recorder>>>,
+    trace: Option<Box<dyn Write>>,
+    symbols: Option<&'a SymbolTable>,
+}
+
+impl Options {
+    fn parse(args: &[String]) -> Result<Options, String> {
+        let mut program_path = None;
+        let mut options = Options {
+            program_path: String::new(),
+            inputs: Vec::new(),
+            interactive: false,
+            output_mode: OutputMode::Numbers,
+            patches: Vec::new(),
+            record_path: None,
+            replay_path: None,
+            list_strings: false,
+            trace: false,
+            symbols_path: None,
+            limits: Default::default(),
+        };
+
+        let mut args = args.iter();
+        while let Some(arg) = args.next() {
+            let mut value = || {
+                args.next()
+                    .ok_or_else(|| format!("Missing value for {}", arg))
+            };
+
+            match arg.as_str() {
+                "--input" => options.inputs.extend(parse_numbers(value()?)?),
+                "--ascii" => options.inputs.extend(ascii_values(&unescape(value()?))),
+                "--interactive" => options.interactive = true,
+                "--output" => {
+                    options.output_mode = match value()?.as_str() {
+                        "numbers" => OutputMode::Numbers,
+                        "ascii" => OutputMode::Ascii,
+                        mode => return Err(format!("Unknown output mode '{}'", mode)),
+                    }
+                }
+                "--set" => options.patches.push(parse_patch(value()?)?),
+                "--record" => options.record_path = Some(value()?.clone()),
+                "--replay" => options.replay_path = Some(value()?.clone()),
+                "--strings" => options.list_strings = true,
+                "--trace" => options.trace = true,
+                "--symbols" => options.symbols_path = Some(value()?.clone()),
+                "--max-instructions" => {
+                    options.limits.max_instructions = Some(parse_limit(value()?)?)
+                }
+                "--max-memory" => options.limits.max_memory = Some(parse_limit(value()?)?),
+                "--max-outputs" => options.limits.max_outputs = Some(parse_limit(value()?)?),
+                "--max-input-wait" => {
+                    let seconds = value()?;
+                    let seconds = seconds
+                        .parse::<f64>()
+                        .ok()
+                        .filter(|seconds| *seconds >= 0.0 && seconds.is_finite())
+                        .ok_or_else(|| format!("'{}' is not a number of seconds", seconds))?;
+                    options.limits.max_input_wait = Some(Duration::from_secs_f64(seconds));
+                }
+                _ if arg.starts_with("--") => return Err(format!("Unknown option {}", arg)),
+                _ if program_path.is_none() => program_path = Some(arg.clone()),
+                _ => return Err(format!("Unexpected argument '{}'", arg)),
+            }
+        }
+
+        options.program_path = program_path.ok_or("No program given")?;
+        if options.symbols_path.is_some() && !options.trace {
+            return Err("--symbols only applies to --trace".to_string());
+        }
+        Ok(options)
+    }
+}
+
+fn parse_numbers(s: &str) -> Result<Vec<i64>, String> {
+    s.split(',')
+        .map(|it| it.trim())
+        .filter(|it| !it.is_empty())
+        .map(|it| {
+            it.parse::<i64>()
+                .map_err(|_| format!("'{}' is not a number", it))
+        })
+        .collect()
+}
+
+fn parse_limit<T: std::str::FromStr>(s: &str) -> Result<T, String> {
+    s.parse()
+        .map_err(|_| format!("'{}' is not a valid limit", s))
+}
+
+fn parse_patch(s: &str) -> Result<(usize, i64), String> {
+    let bad_patch = || format!("Expected ADDR=VALUE, got '{}'", s);
+
+    let mut parts = s.splitn(2, '=');
+    let addr = parts.next().and_then(|it| it.trim().parse::<usize>().ok());
+    let value = parts.next().and_then(|it| it.trim().parse::<i64>().ok());
+    match (addr, value) {
+        (Some(addr), _) if addr >= MAX_INTCODE_SIZE => Err(format!(
+            "Can't set address {}, memory only holds {} words",
+            addr, MAX_INTCODE_SIZE
+        )),
+        (Some(addr), Some(value)) => Ok((addr, value)),
+        _ => Err(bad_patch()),
+    }
+}
+
+fn unescape(s: &str) -> String {
+    s.replace("\\n", "\n")
+}
+
+fn ascii_values(s: &str) -> Vec<i64> {
+    s.chars().map(|c| c as i64).collect()
+}
+
+/// Loads a text program or an image, along with the symbols saved in the image.
+fn load_program(data: &[u8]) -> Result<(Vec<i64>, Option<SymbolTable>), String> {
+    if is_image(data) {
+        let image = load_image(data).map_err(|e| e.to_string())?;
+        let symbols = image.metadata.as_ref().map(SymbolTable::from_metadata);
+        Ok((image.memory(), symbols))
+    } else {
+        let text = std::str::from_utf8(data).map_err(|e| e.to_string())?;
+        let memory = load_intcode(text).map_err(|e| e.to_string())?;
+        Ok((memory, None))
+    }
+}
+
+fn run(
+    options: &Options,
+    program: &[u8],
+    session: Session,
+    input: &mut dyn BufRead,
+    out: &mut dyn Write,
+) -> i32 {
+    let (mut memory, image_symbols) = match load_program(program) {
+        Ok(loaded) => loaded,
+        Err(e) => {
+            eprintln!("Can't load program: {}", e);
+            return EXIT_ERROR;
+        }
+    };
+    for (addr, value) in options.patches.iter() {
+        if *addr >= memory.len() {
+            memory.resize(*addr + 1, 0);
+        }
+        memory[*addr] = *value;
+    }
+    if options.list_strings {
+        return list_strings(&memory, options.inputs.clone(), options.limits, out);
+    }
+
+    let mut program = match IntcodeProgram::try_init(&memory, Vec::new()) {
+        Ok(program) => program,
+        Err(e) => {
+            eprintln!("Can't load program: {}", e);
+            return EXIT_ERROR;
+        }
+    };
+    program.set_limits(options.limits);
+    if let Some(recorder) = session.recorder {
+        program.add_observer(recorder);
+    }
+    if let Some(trace) = session.trace {
+        let symbols = session
+            .symbols
+            .cloned()
+            .or(image_symbols)
+            .unwrap_or_default();
+        let log = DebugLog::with_symbols(symbols).writing_to(trace);
+        program.add_observer(Rc::new(RefCell::new(log)));
+    }
+    if let Some(transcript) = session.replay {
+        match replay(&mut program, transcript) {
+            Ok(outputs) => outputs
+                .into_iter()
+                .for_each(|output| write_output(out, &options.output_mode, output)),
+            Err(e) => {
+                eprintln!("Replay failed: {}", e);
+                return EXIT_ERROR;
+            }
+        }
+    }
+    options
+        .inputs
+        .iter()
+        .for_each(|value| program.buffer_input(*value));
+
+    loop {
+        let result = program.try_run();
+
+        while let Some(output) = program.consume_output() {
+            write_output(out, &options.output_mode, output);
+        }
+        out.flush().unwrap_or_default();
+
+        if let Err(e) = result {
+            eprintln!("{}", e);
+            return EXIT_ERROR;
+        }
+        if program.is_halted() {
+            return EXIT_HALTED;
+        }
+
+        // awaiting input
+        let more_input = if options.interactive {
+            read_input_line(input, &options.output_mode)
+        } else {
+            None
+        };
+        match more_input {
+            Some(values) => values.iter().for_each(|v| program.buffer_input(*v)),
+            None => {
+                eprintln!("Program is waiting for input");
+                return EXIT_STARVED;
+            }
+        }
+    }
+}
+
+/// The shortest string worth listing.
+const MIN_STRING_LEN: usize = 4;
+
+fn list_strings(memory: &[i64], inputs: Vec<i64>, limits: Limits, out: &mut dyn Write) -> i32 {
+    let write_strings = |out: &mut dyn Write, heading: &str, strings: &[FoundString]| {
+        writeln!(out, "{}:", heading).unwrap_or_default();
+        for string in strings {
+            writeln!(
+                out,
+                "{:>6}..{:<6} {:?}",
+                string.range.start, string.range.end, string.text
+            )
+            .unwrap_or_default();
+        }
+    };
+
+    write_strings(out, "Stored", &scan_strings(memory, MIN_STRING_LEN));
+    match printed_strings(memory, inputs, MIN_STRING_LEN, limits) {
+        Ok(strings) => {
+            write_strings(out, "Printed", &strings);
+            EXIT_HALTED
+        }
+        Err(e) => {
+            eprintln!("{}", e);
+            EXIT_ERROR
+        }
+    }
+}
+
+fn write_output(out: &mut dyn Write, output_mode: &OutputMode, value: i64) {
+    let written = match output_mode {
+        OutputMode::Ascii if (0..128).contains(&value) => write!(out, "{}", value as u8 as char),
+        // values outside of ASCII are usually the answer, so print them on their own line
+        _ => writeln!(out, "{}", value),
+    };
+    written.unwrap_or_default();
+}
+
+/// Reads a line of input from stdin, interpreting it the same way as the program's output. Keeps
+/// asking until there's valid input, or returns `None` at the end of the input.
+fn read_input_line(input: &mut dyn BufRead, output_mode: &OutputMode) -> Option<Vec<i64>> {
+    loop {
+        let mut line = String::new();
+        match input.read_line(&mut line) {
+            Ok(0) | Err(_) => return None,
+            Ok(_) => {}
+        }
+
+        match output_mode {
+            OutputMode::Ascii => return Some(ascii_values(&line)),
+            OutputMode::Numbers => match parse_numbers(&line) {
+                Ok(values) if !values.is_empty() => return Some(values),
+                Ok(_) => {}
+                Err(e) => eprintln!("{}", e),
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::*;
+    use intcode::image::{save_image, IntcodeImage, Metadata};
+
+    const ECHO_TWICE: &str = "3,0,4,0,3,0,4,0,99";
+
+    fn args(s: &str) -> Vec<String> {
+        s.split(' ').map(|it| it.to_string()).collect()
+    }
+
+    fn run_with(options: &str, program: &str, stdin: &str) -> (i32, String) {
+        run_bytes_with(options, program.as_bytes(), stdin)
+    }
+
+    fn run_bytes_with(options: &str, program: &[u8], stdin: &str) -> (i32, String) {
+        run_session(options, program, Session::default(), stdin)
+    }
+
+    fn run_session(options: &str, program: &[u8], session: Session, stdin: &str) -> (i32, String) {
+        let options = Options::parse(&args(options)).unwrap();
+        let mut out = Vec::new();
+        let status = run(&options, program, session, &mut stdin.as_bytes(), &mut out);
+        (status, String::from_utf8(out).unwrap())
+    }
+
+    /// A trace that stays readable after the session takes it.
+    #[derive(Clone, Default)]
+    struct TraceBuffer(Rc<RefCell<Vec<u8>>>);
+
+    impl Write for TraceBuffer {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0.borrow_mut().write(buf)
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    fn run_traced(program: &[u8], symbols: Option<&SymbolTable>) -> Vec<String> {
+        let trace = TraceBuffer::default();
+        let session = Session {
+            trace: Some(Box::new(trace.clone())),
+            symbols,
+            ..Default::default()
+        };
+        let (status, _) = run_session("p --trace --input 5", program, session, "");
+        assert_eq!(status, EXIT_HALTED);
+        let text = String::from_utf8(trace.0.borrow().clone()).unwrap();
+        text.lines().map(|line| line.to_string()).collect()
+    }
+
+    #[test]
+    fn test_parse_options() {
+        let options =
+            Options::parse(&args("p.txt --input 1,2 --set 0=2 --ascii hi\\n --input 3")).unwrap();
+        assert_eq!(options.program_path, "p.txt");
+        assert_eq!(options.inputs, vec![1, 2, 104, 105, 10, 3]);
+        assert_eq!(options.patches, vec![(0, 2)]);
+        assert_eq!(options.output_mode, OutputMode::Numbers);
+        assert!(!options.interactive);
+
+        let options = Options::parse(&args("p.txt --record a.txt --replay b.txt")).unwrap();
+        assert_eq!(options.record_path, Some("a.txt".to_string()));
+        assert_eq!(options.replay_path, Some("b.txt".to_string()));
+
+        let options = Options::parse(&args("p.txt --trace --symbols p.sym")).unwrap();
+        assert!(options.trace);
+        assert_eq!(options.symbols_path, Some("p.sym".to_string()));
+    }
+
+    #[test]
+    fn test_parse_limits() {
+        let options = Options::parse(&args(
+            "p.txt --max-instructions 100 --max-memory 2048 --max-outputs 10 --max-input-wait 1.5",
+        ))
+        .unwrap();
+        assert_eq!(
+            options.limits,
+            Limits {
+                max_instructions: Some(100),
+                max_memory: Some(2048),
+                max_outputs: Some(10),
+                max_input_wait: Some(Duration::from_millis(1500)),
+            }
+        );
+        assert!(Options::parse(&args("p.txt --max-outputs -1")).is_err());
+        assert!(Options::parse(&args("p.txt --max-input-wait forever")).is_err());
+    }
+
+    #[test]
+    fn test_parse_bad_options() {
+        assert!(Options::parse(&args("--interactive")).is_err());
+        assert!(Options::parse(&args("p.txt --set 0")).is_err());
+        assert!(Options::parse(&args("p.txt --set 40000=1")).is_err());
+        assert!(Options::parse(&args("p.txt --input 1,x")).is_err());
+        assert!(Options::parse(&args("p.txt --output hex")).is_err());
+        assert!(Options::parse(&args("p.txt --input")).is_err());
+        assert!(Options::parse(&args("p.txt --symbols p.sym")).is_err());
+        assert!(Options::parse(&args("p.txt q.txt")).is_err());
+    }
+
+    #[test]
+    fn test_run_until_halted() {
+        let (status, out) = run_with("p --input 7,8", ECHO_TWICE, "");
+        assert_eq!(status, EXIT_HALTED);
+        assert_eq!(out, "7\n8\n");
+    }
+
+    #[test]
+    fn test_run_starved() {
+        let (status, out) = run_with("p --input 7", ECHO_TWICE, "8\n");
+        assert_eq!(status, EXIT_STARVED);
+        assert_eq!(out, "7\n");
+    }
+
+    #[test]
+    fn test_run_interactive() {
+        let (status, out) = run_with("p --interactive", ECHO_TWICE, "\n7\nx\n8\n");
+        assert_eq!(status, EXIT_HALTED);
+        assert_eq!(out, "7\n8\n");
+    }
+
+    #[test]
+    fn test_run_ascii() {
+        let (status, out) = run_with("p --output ascii --interactive", ECHO_TWICE, "hi");
+        assert_eq!(status, EXIT_HALTED);
+        assert_eq!(out, "hi");
+
+        let (_, out) = run_with("p --output ascii --input 33,1000", ECHO_TWICE, "");
+        assert_eq!(out, "!1000\n");
+    }
+
+    #[test]
+    fn test_run_with_patches() {
+        let (status, out) = run_with("p --set 1=6 --set 6=42", "4,0,99", "");
+        assert_eq!(status, EXIT_HALTED);
+        assert_eq!(out, "42\n");
+    }
+
+    #[test]
+    fn test_run_too_large() {
+        let program = vec!["99"; MAX_INTCODE_SIZE + 1].join(",");
+        let (status, _) = run_with("p", &program, "");
+        assert_eq!(status, EXIT_ERROR);
+
+        let (status, _) = run_with("p --strings", &program, "");
+        assert_eq!(status, EXIT_ERROR);
+    }
+
+    #[test]
+    fn test_run_image() {
+        let image = save_image(&IntcodeImage::from_memory(&[3, 0, 4, 0, 99]));
+        let (status, out) = run_bytes_with("p --input 5", &image, "");
+        assert_eq!(status, EXIT_HALTED);
+        assert_eq!(out, "5\n");
+    }
+
+    #[test]
+    fn test_trace_names_addresses() {
+        let program = "3,5,4,5,99,0";
+        let trace = run_traced(program.as_bytes(), None);
+        assert_eq!(trace[0], "PC(0) :: 3");
+        assert!(trace.contains(&"WRITE [5] <- 5".to_string()));
+        assert_eq!(trace.last().unwrap(), "HALT");
+
+        let symbols = SymbolTable::parse("0..5 main function\n5 value").unwrap();
+        let trace = run_traced(program.as_bytes(), Some(&symbols));
+        assert_eq!(trace[0], "PC(main) :: 3");
+        assert!(trace.contains(&"WRITE [value] <- 5".to_string()));
+        assert!(trace.contains(&"READ [value] -> 5".to_string()));
+        assert!(trace.contains(&"OUTPUT 5".to_string()));
+
+        // symbols saved in an image, unless a symbol file overrides them
+        let mut image = IntcodeImage::from_memory(&[3, 5, 4, 5, 99, 0]);
+        image.metadata = Some(Metadata {
+            symbols: vec![(5, "saved".to_string())],
+            ..Default::default()
+        });
+        let image = save_image(&image);
+        let trace = run_traced(&image, None);
+        assert!(trace.contains(&"WRITE [saved] <- 5".to_string()));
+        let trace = run_traced(&image, Some(&symbols));
+        assert!(trace.contains(&"WRITE [value] <- 5".to_string()));
+    }
+
+    #[test]
+    fn test_record_and_replay() {
+        let recorder = Rc::new(RefCell::new(Recorder::default()));
+        let session = Session {
+            recorder: Some(recorder.clone()),
+            ..Default::default()
+        };
+        let (status, _) = run_session("p --interactive", ECHO_TWICE.as_bytes(), session, "7\n");
+        assert_eq!(status, EXIT_STARVED);
+        let transcript = recorder.borrow().transcript().clone();
+        assert_eq!(transcript.to_string(), "in 7\nout 7\n");
+
+        // continue the recorded session
+        let session = Session {
+            replay: Some(&transcript),
+            ..Default::default()
+        };
+        let (status, out) = run_session("p --input 8", ECHO_TWICE.as_bytes(), session, "");
+        assert_eq!(status, EXIT_HALTED);
+        assert_eq!(out, "7\n8\n");
+
+        let session = Session {
+            replay: Some(&transcript),
+            ..Default::default()
+        };
+        let (status, _) = run_session("p", "3,0,4,0,4,0,99".as_bytes(), session, "");
+        assert_eq!(status, EXIT_ERROR);
+    }
+
+    #[test]
+    fn test_list_strings() {
+        // prints "Hi!\n" from where it's stored, after its length
+        let program = "109,16,204,0,109,1,1001,15,-1,15,1005,15,2,99,0,4,72,105,33,10";
+        let (status, out) = run_with("p --strings", program, "");
+        assert_eq!(status, EXIT_HALTED);
+        assert_eq!(
+            out,
+            "Stored:\n    16..20     \"Hi!\\n\"\nPrinted:\n    16..20     \"Hi!\\n\"\n"
+        );
+    }
+
+    #[test]
+    fn test_list_strings_of_endless_program() {
+        // prints "Hi!\n", then zeros forever
+        let program = "109,17,204,0,109,1,1001,16,-1,16,1005,16,2,1105,1,0,4,72,105,33,10";
+        let (status, out) = run_with("p --strings --max-instructions 1000", program, "");
+        assert_eq!(status, EXIT_HALTED);
+        assert!(out.ends_with("Printed:\n    17..21     \"Hi!\\n\"\n"));
+    }
+
+    #[test]
+    fn test_run_with_limits() {
+        // outputs 1 forever
+        let (status, out) = run_with("p --max-instructions 5", "104,1,1105,1,0", "");
+        assert_eq!(status, EXIT_ERROR);
+        assert_eq!(out, "1\n1\n1\n");
+    }
+
+    #[test]
+    fn test_run_error() {
+        let (status, _) = run_with("p", "4,0,98", "");
+        assert_eq!(status, EXIT_ERROR);
+
+        let (status, _) = run_with("p", "4,0,\n9 9", "");
+        assert_eq!(status, EXIT_ERROR);
+    }
+}
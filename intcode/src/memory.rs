@@ -0,0 +1,247 @@
+use crate::device::Device;
+use std::cell::{Cell, RefCell};
+use std::ops::Range;
+use std::rc::Rc;
+
+const PAGE_SIZE: usize = 1024;
+
+type Page = [i64; PAGE_SIZE];
+
+type SharedDevice = Rc<RefCell<dyn Device>>;
+
+/// Program memory, split into fixed-size pages that are shared between clones until one of them
+/// writes to it. Pages that have never been written are all zeros and aren't allocated at all.
+///
+/// Address ranges can also be mapped to devices, which handle loads and stores in that range
+/// instead of the pages. Clones get their own copies of the devices.
+///
+/// A hash of the contents of the pages is kept up to date on every store, see `hash`.
+pub(crate) struct Memory {
+    pages: Vec<Option<Rc<Page>>>,
+    devices: Vec<MappedDevice>,
+    /// How many loads and stores have gone to devices, which can change their state.
+    device_accesses: Cell<u64>,
+    hash: u64,
+}
+
+struct MappedDevice {
+    range: Range<usize>,
+    device: SharedDevice,
+}
+
+impl Clone for Memory {
+    fn clone(&self) -> Memory {
+        // a device mapped to several ranges is copied once, so the copy is still shared by them
+        let mut copies: Vec<(&SharedDevice, SharedDevice)> = Vec::new();
+        let mut devices = Vec::new();
+        for mapped in self.devices.iter() {
+            let copy = match copies
+                .iter()
+                .find(|(device, _)| Rc::ptr_eq(device, &mapped.device))
+            {
+                Some((_, copy)) => copy.clone(),
+                None => {
+                    let copy = mapped.device.borrow().fork_device();
+                    copies.push((&mapped.device, copy.clone()));
+                    copy
+                }
+            };
+            devices.push(MappedDevice {
+                range: mapped.range.clone(),
+                device: copy,
+            });
+        }
+
+        Memory {
+            pages: self.pages.clone(),
+            devices,
+            device_accesses: self.device_accesses.clone(),
+            hash: self.hash,
+        }
+    }
+}
+
+impl Memory {
+    pub(crate) fn with_size(size: usize) -> Memory {
+        let num_pages = size.div_ceil(PAGE_SIZE);
+        Memory {
+            pages: vec![None; num_pages],
+            devices: Vec::new(),
+            device_accesses: Cell::new(0),
+            hash: 0,
+        }
+    }
+
+    /// Maps `range` to `device`, returning the range of an already mapped device if they overlap.
+    pub(crate) fn map_device(
+        &mut self,
+        range: Range<usize>,
+        device: SharedDevice,
+    ) -> Result<(), Range<usize>> {
+        let overlapping = self
+            .devices
+            .iter()
+            .find(|mapped| range.start < mapped.range.end && mapped.range.start < range.end);
+        if let Some(mapped) = overlapping {
+            return Err(mapped.range.clone());
+        }
+        self.devices.push(MappedDevice { range, device });
+        Ok(())
+    }
+
+    fn device_at(&self, addr: usize) -> Option<&MappedDevice> {
+        // most programs don't map any devices, so keep their loads and stores to one extra check
+        if self.devices.is_empty() {
+            return None;
+        }
+        self.devices
+            .iter()
+            .find(|mapped| mapped.range.contains(&addr))
+    }
+
+    pub(crate) fn load_from(&mut self, values: &[i64]) {
+        for (addr, value) in values.iter().enumerate() {
+            self.set(addr, *value);
+        }
+    }
+
+    /// Whether `addr` is in memory or in a mapped device.
+    pub(crate) fn contains(&self, addr: usize) -> bool {
+        addr < self.pages.len() * PAGE_SIZE || self.device_at(addr).is_some()
+    }
+
+    /// Loads the value at `addr` for an executed instruction, which a device may react to.
+    pub(crate) fn get(&self, addr: usize) -> i64 {
+        if let Some(mapped) = self.device_at(addr) {
+            self.device_accesses.set(self.device_accesses.get() + 1);
+            return mapped.device.borrow_mut().load(addr - mapped.range.start);
+        }
+        self.page_value(addr)
+    }
+
+    /// The value at `addr` without any device side effects, for reading memory from outside the
+    /// program.
+    pub(crate) fn peek(&self, addr: usize) -> i64 {
+        if let Some(mapped) = self.device_at(addr) {
+            return mapped.device.borrow().peek(addr - mapped.range.start);
+        }
+        self.page_value(addr)
+    }
+
+    fn page_value(&self, addr: usize) -> i64 {
+        match &self.pages[addr / PAGE_SIZE] {
+            Some(page) => page[addr % PAGE_SIZE],
+            None => 0,
+        }
+    }
+
+    pub(crate) fn set(&mut self, addr: usize, value: i64) {
+        if let Some(mapped) = self.device_at(addr) {
+            self.device_accesses.set(self.device_accesses.get() + 1);
+            mapped
+                .device
+                .borrow_mut()
+                .store(addr - mapped.range.start, value);
+            return;
+        }
+
+        let page = self.pages[addr / PAGE_SIZE].get_or_insert_with(|| Rc::new([0; PAGE_SIZE]));
+
+        // copies the page first if another program is still sharing it
+        let cell = &mut Rc::make_mut(page)[addr % PAGE_SIZE];
+        self.hash ^= cell_hash(addr, *cell) ^ cell_hash(addr, value);
+        *cell = value;
+    }
+
+    /// The number of loads and stores that have gone to devices so far.
+    pub(crate) fn device_accesses(&self) -> u64 {
+        self.device_accesses.get()
+    }
+
+    /// The number of words in pages that have been written, not counting devices.
+    pub(crate) fn allocated(&self) -> usize {
+        self.pages.iter().filter(|page| page.is_some()).count() * PAGE_SIZE
+    }
+
+    /// How many more words writing to `addr` would allocate.
+    pub(crate) fn allocation_for(&self, addr: usize) -> usize {
+        if self.device_at(addr).is_some() || self.pages[addr / PAGE_SIZE].is_some() {
+            0
+        } else {
+            PAGE_SIZE
+        }
+    }
+
+    /// A hash of every value in memory (not including devices), so equal memory has an equal hash.
+    /// It's the XOR of a hash of each non-zero cell, so a store only has to swap out the old value's
+    /// hash for the new one's.
+    pub(crate) fn hash(&self) -> u64 {
+        self.hash
+    }
+}
+
+/// The hash of one cell, which `hash` combines for the whole of memory.
+pub(crate) fn cell_hash(addr: usize, value: i64) -> u64 {
+    if value == 0 {
+        return 0;
+    }
+    mix(mix(addr as u64) ^ value as u64)
+}
+
+/// The splitmix64 finalizer.
+pub(crate) fn mix(value: u64) -> u64 {
+    let mut z = value.wrapping_add(0x9E37_79B9_7F4A_7C15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    z ^ (z >> 31)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::memory::{Memory, PAGE_SIZE};
+    use std::rc::Rc;
+
+    fn shares_page(a: &Memory, b: &Memory, page: usize) -> bool {
+        match (&a.pages[page], &b.pages[page]) {
+            (Some(a), Some(b)) => Rc::ptr_eq(a, b),
+            _ => false,
+        }
+    }
+
+    #[test]
+    fn test_unwritten_memory_is_zero() {
+        let memory = Memory::with_size(4 * PAGE_SIZE);
+        assert_eq!(memory.get(0), 0);
+        assert_eq!(memory.get(4 * PAGE_SIZE - 1), 0);
+        assert!(memory.pages.iter().all(Option::is_none));
+    }
+
+    #[test]
+    fn test_clone_shares_pages_until_written() {
+        let mut parent = Memory::with_size(4 * PAGE_SIZE);
+        parent.load_from(&[1, 2, 3]);
+        parent.set(3 * PAGE_SIZE, 4);
+
+        let mut child = parent.clone();
+        assert!(shares_page(&parent, &child, 0));
+        assert!(shares_page(&parent, &child, 3));
+
+        child.set(1, 20);
+        assert_eq!(child.get(1), 20);
+        assert_eq!(parent.get(1), 2);
+        assert!(!shares_page(&parent, &child, 0));
+        assert!(shares_page(&parent, &child, 3));
+    }
+
+    #[test]
+    fn test_allocation() {
+        let mut memory = Memory::with_size(4 * PAGE_SIZE);
+        assert_eq!(memory.allocated(), 0);
+        assert_eq!(memory.allocation_for(5), PAGE_SIZE);
+
+        memory.set(5, 1);
+        assert_eq!(memory.allocated(), PAGE_SIZE);
+        assert_eq!(memory.allocation_for(PAGE_SIZE - 1), 0);
+        assert_eq!(memory.allocation_for(PAGE_SIZE), PAGE_SIZE);
+    }
+}
@@ -0,0 +1,421 @@
+//! Running several programs that send each other their outputs, e.g. chained amplifiers or a
+//! network of NICs.
+//!
+//! The network runs every program in turn, one round at a time. Outputs are grouped into frames
+//! and passed to a routing function, which decides which program's input queue each frame goes to.
+//! It keeps going until every program halts, or until it can tell that no more progress will be
+//! made without outside help:
+//!
+//! * **Deadlock**: every program that hasn't halted is waiting for input, and nothing is queued
+//!   for any of them.
+//! * **Livelock**: programs that never block, because they're given an idle input (e.g. `-1`)
+//!   whenever their queue is empty, only read the idle input round after round, and get back to a
+//!   state they were all in before. Nothing was queued or output in between, so they would keep
+//!   going around the same rounds forever. States are compared by fingerprint the same way as
+//!   `IntcodeProgram::set_cycle_detection`, so the rounds may go around a few times first.
+
+use crate::fingerprint::CycleDetector;
+use crate::memory::mix;
+use crate::{IntcodeError, IntcodeProgram};
+use std::collections::VecDeque;
+use std::fmt::{Display, Formatter};
+
+/// Where a frame of outputs goes, as decided by the routing function.
+#[derive(Debug, PartialEq)]
+pub enum Route {
+    /// Queue these values as input to another program.
+    To(usize, Vec<i64>),
+    /// The frame was handled outside the network, e.g. sent to a device that isn't a program.
+    Drop,
+    /// Stop running the network after this frame.
+    Stop,
+}
+
+/// Why the network stopped running.
+#[derive(Debug, PartialEq)]
+pub enum Stop {
+    AllHalted,
+    /// The routing function returned [`Route::Stop`] for a frame from this program.
+    Stopped {
+        program: usize,
+    },
+    Deadlock(Vec<Blocked>),
+    Livelock(Vec<Blocked>),
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Blocked {
+    pub program: usize,
+    pub reason: BlockReason,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum BlockReason {
+    /// Waiting for input, and nothing is queued for it.
+    AwaitingInput,
+    /// Only reading the idle input, without producing any output, in a loop.
+    Polling,
+}
+
+#[derive(Debug)]
+pub enum NetworkError {
+    /// A program in the network failed.
+    Program { program: usize, error: IntcodeError },
+    /// The routing function sent a frame from `program` to a program that isn't in the network.
+    NoSuchProgram { program: usize, to: usize },
+    /// `send` was given a program that isn't in the network.
+    NotInNetwork { to: usize },
+}
+
+impl Display for NetworkError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), std::fmt::Error> {
+        match self {
+            NetworkError::Program { program, error } => write!(f, "Program {}: {}", program, error),
+            NetworkError::NoSuchProgram { program, to } => write!(
+                f,
+                "Program {}: sent a frame to program {}, which isn't in the network",
+                program, to
+            ),
+            NetworkError::NotInNetwork { to } => {
+                write!(
+                    f,
+                    "Can't send to program {}, which isn't in the network",
+                    to
+                )
+            }
+        }
+    }
+}
+
+impl std::error::Error for NetworkError {}
+
+pub struct Network {
+    programs: Vec<IntcodeProgram>,
+    queues: Vec<VecDeque<i64>>,
+    /// Outputs that aren't a whole frame yet.
+    pending: Vec<Vec<i64>>,
+    frame_size: usize,
+    idle_input: Option<i64>,
+}
+
+impl Network {
+    pub fn new(programs: Vec<IntcodeProgram>) -> Network {
+        let num_programs = programs.len();
+        Network {
+            programs,
+            queues: vec![VecDeque::new(); num_programs],
+            pending: vec![Vec::new(); num_programs],
+            frame_size: 1,
+            idle_input: None,
+        }
+    }
+
+    /// Routes outputs in frames of `frame_size` values instead of one at a time.
+    pub fn with_frame_size(mut self, frame_size: usize) -> Network {
+        assert!(frame_size > 0, "Frames need at least one value");
+        self.frame_size = frame_size;
+        self
+    }
+
+    /// Gives programs `value` whenever they're waiting for input and nothing is queued, so they
+    /// never block. The network then stops on livelock rather than deadlock.
+    pub fn with_idle_input(mut self, value: i64) -> Network {
+        self.idle_input = Some(value);
+        self
+    }
+
+    pub fn program(&self, i: usize) -> &IntcodeProgram {
+        &self.programs[i]
+    }
+
+    /// Queues `values` as input to program `to`, e.g. to restart a network after it stopped.
+    pub fn send(&mut self, to: usize, values: &[i64]) -> Result<(), NetworkError> {
+        self.queues
+            .get_mut(to)
+            .ok_or(NetworkError::NotInNetwork { to })?
+            .extend(values);
+        Ok(())
+    }
+
+    /// Runs every program until the network stops, passing each frame of outputs to `route` along
+    /// with the index of the program that produced it.
+    pub fn run<R>(&mut self, mut route: R) -> Result<Stop, NetworkError>
+    where
+        R: FnMut(usize, &[i64]) -> Route,
+    {
+        // looks for a repeat of the state after an idle round, since the last round that wasn't
+        let mut idle_rounds: Option<CycleDetector> = None;
+        loop {
+            let mut idle_round = true;
+
+            for i in 0..self.programs.len() {
+                let program = &mut self.programs[i];
+                if program.is_halted() {
+                    continue;
+                }
+
+                if program.is_awaiting_input() && program.input_buf.is_empty() {
+                    if !self.queues[i].is_empty() {
+                        self.queues[i]
+                            .drain(..)
+                            .for_each(|value| program.buffer_input(value));
+                        idle_round = false;
+                    } else if let Some(idle_input) = self.idle_input {
+                        program.buffer_input(idle_input);
+                    } else {
+                        continue;
+                    }
+                } else {
+                    // running for the first time, or with input it was given directly
+                    idle_round = false;
+                }
+
+                let result = program.try_run();
+
+                while let Some(output) = self.programs[i].consume_output() {
+                    idle_round = false;
+                    self.pending[i].push(output);
+                    if self.pending[i].len() < self.frame_size {
+                        continue;
+                    }
+
+                    let frame: Vec<i64> = self.pending[i].drain(..).collect();
+                    match route(i, &frame) {
+                        Route::To(to, values) => match self.queues.get_mut(to) {
+                            Some(queue) => queue.extend(values),
+                            None => return Err(NetworkError::NoSuchProgram { program: i, to }),
+                        },
+                        Route::Drop => {}
+                        Route::Stop => return Ok(Stop::Stopped { program: i }),
+                    }
+                }
+                result.map_err(|error| NetworkError::Program { program: i, error })?;
+            }
+
+            if self.programs.iter().all(|program| program.is_halted()) {
+                return Ok(Stop::AllHalted);
+            }
+            if self.idle_input.is_some() {
+                if !idle_round {
+                    idle_rounds = None;
+                    continue;
+                }
+                let hash = self.machine_hash();
+                match &mut idle_rounds {
+                    Some(detector) => {
+                        if detector.is_repeat(hash) {
+                            return Ok(Stop::Livelock(self.blocked(BlockReason::Polling)));
+                        }
+                    }
+                    None => idle_rounds = Some(CycleDetector::new(hash)),
+                }
+            } else if self.is_deadlocked() {
+                return Ok(Stop::Deadlock(self.blocked(BlockReason::AwaitingInput)));
+            }
+        }
+    }
+
+    /// A hash of the state of every program, for telling when the network gets back to a state.
+    fn machine_hash(&self) -> u64 {
+        self.programs
+            .iter()
+            .fold(0, |hash, program| mix(hash ^ program.fingerprint()))
+    }
+
+    fn is_deadlocked(&self) -> bool {
+        self.programs
+            .iter()
+            .zip(self.queues.iter())
+            .all(|(program, queue)| {
+                program.is_halted()
+                    || (program.is_awaiting_input()
+                        && program.input_buf.is_empty()
+                        && queue.is_empty())
+            })
+    }
+
+    fn blocked(&self, reason: BlockReason) -> Vec<Blocked> {
+        (0..self.programs.len())
+            .filter(|i| !self.programs[*i].is_halted())
+            .map(|program| Blocked { program, reason })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::network::*;
+    use crate::parse_intcode_input;
+
+    const ECHO: &str = "3,10,4,10,1105,1,0";
+    const ECHO_ONCE: &str = "3,0,4,0,99";
+
+    fn programs(intcode: &str, n: usize) -> Vec<IntcodeProgram> {
+        (0..n).map(|_| IntcodeProgram::init_from(intcode)).collect()
+    }
+
+    #[test]
+    fn test_chain_until_halted() {
+        let mut network = Network::new(programs(ECHO_ONCE, 3));
+        network.send(0, &[42]).unwrap();
+
+        let mut result = None;
+        let stop = network.run(|from, frame| {
+            if from == 2 {
+                result = Some(frame[0]);
+                Route::Drop
+            } else {
+                Route::To(from + 1, frame.to_vec())
+            }
+        });
+        assert_eq!(stop.unwrap(), Stop::AllHalted);
+        assert_eq!(result, Some(42));
+    }
+
+    #[test]
+    fn test_deadlock() {
+        // program 1 halts, program 0 and 2 wait for each other
+        let mut programs = programs(ECHO, 3);
+        programs[1] = IntcodeProgram::init_from("99");
+        let mut network = Network::new(programs);
+
+        let stop = network.run(|_, frame| Route::To(0, frame.to_vec()));
+        let blocked = |program| Blocked {
+            program,
+            reason: BlockReason::AwaitingInput,
+        };
+        assert_eq!(stop.unwrap(), Stop::Deadlock(vec![blocked(0), blocked(2)]));
+
+        // sending something breaks the deadlock until it's been echoed back to program 0 forever
+        network.send(2, &[5]).unwrap();
+        let mut routed = 0;
+        let stop = network.run(|from, _| {
+            routed += 1;
+            if routed == 10 {
+                Route::Stop
+            } else {
+                Route::To(if from == 0 { 2 } else { 0 }, vec![5])
+            }
+        });
+        assert_eq!(stop.unwrap(), Stop::Stopped { program: 0 });
+    }
+
+    #[test]
+    fn test_livelock_with_idle_input() {
+        // outputs its input unless it's -1
+        let poller = "3,20,1008,20,-1,21,1005,21,0,4,20,1105,1,0";
+        let mut network = Network::new(programs(poller, 2)).with_idle_input(-1);
+        network.send(0, &[1, 2]).unwrap();
+
+        let mut received = Vec::new();
+        let stop = network.run(|_, frame| {
+            received.push(frame[0]);
+            Route::To(1, vec![])
+        });
+        assert_eq!(received, vec![1, 2]);
+        match stop.unwrap() {
+            Stop::Livelock(blocked) => {
+                assert_eq!(blocked.len(), 2);
+                assert!(blocked.iter().all(|b| b.reason == BlockReason::Polling));
+            }
+            stop => panic!("Expected livelock, got {:?}", stop),
+        }
+    }
+
+    #[test]
+    fn test_polling_with_changing_state_is_not_livelock() {
+        // counts the inputs it reads, and outputs 7 once it's read 3
+        let counter = "3,20,1001,30,1,30,1008,30,3,21,1006,21,0,104,7,99";
+        let mut network = Network::new(programs(counter, 1)).with_idle_input(-1);
+
+        let mut received = Vec::new();
+        let stop = network.run(|_, frame| {
+            received.push(frame[0]);
+            Route::Drop
+        });
+        assert_eq!(stop.unwrap(), Stop::AllHalted);
+        assert_eq!(received, vec![7]);
+    }
+
+    #[test]
+    fn test_route_to_missing_program() {
+        let mut network = Network::new(programs("104,1,99", 2));
+        let result = network.run(|_, frame| Route::To(5, frame.to_vec()));
+        assert_eq!(
+            result.unwrap_err().to_string(),
+            "Program 0: sent a frame to program 5, which isn't in the network"
+        );
+
+        assert_eq!(
+            network.send(2, &[1]).unwrap_err().to_string(),
+            "Can't send to program 2, which isn't in the network"
+        );
+    }
+
+    #[test]
+    fn test_frames_and_errors() {
+        let mut network = Network::new(vec![IntcodeProgram::init_from(
+            "104,1,104,2,104,3,104,4,98",
+        )])
+        .with_frame_size(2);
+        let mut frames = Vec::new();
+        let result = network.run(|_, frame| {
+            frames.push(frame.to_vec());
+            Route::Drop
+        });
+        assert_eq!(frames, vec![vec![1, 2], vec![3, 4]]);
+        assert_eq!(
+            result.unwrap_err().to_string(),
+            "Program 0: PC 8: Invalid opcode: 98"
+        );
+    }
+
+    fn amplifier_signal(phases: &[i64]) -> i64 {
+        let memory = parse_intcode_input(include_str!("../../day7/day7.txt"));
+        let amps = phases
+            .iter()
+            .map(|phase| IntcodeProgram::init(&memory, vec![*phase]))
+            .collect();
+        let mut network = Network::new(amps);
+        network.send(0, &[0]).unwrap();
+
+        let mut signal = None;
+        let stop = network.run(|from, frame| {
+            if from == phases.len() - 1 {
+                signal = Some(frame[0]);
+            }
+            Route::To((from + 1) % phases.len(), frame.to_vec())
+        });
+        assert_eq!(stop.unwrap(), Stop::AllHalted);
+        signal.unwrap()
+    }
+
+    fn permutations(values: &[i64]) -> Vec<Vec<i64>> {
+        if values.len() <= 1 {
+            return vec![values.to_vec()];
+        }
+        let mut result = Vec::new();
+        for (i, first) in values.iter().enumerate() {
+            let mut rest = values.to_vec();
+            rest.remove(i);
+            for mut permutation in permutations(&rest) {
+                permutation.insert(0, *first);
+                result.push(permutation);
+            }
+        }
+        result
+    }
+
+    #[test]
+    fn test_day7_amplifiers() {
+        let best = |phases: &[i64]| {
+            permutations(phases)
+                .iter()
+                .map(|permutation| amplifier_signal(permutation))
+                .max()
+                .unwrap()
+        };
+        assert_eq!(best(&[0, 1, 2, 3, 4]), 14902);
+        assert_eq!(best(&[5, 6, 7, 8, 9]), 6489132);
+    }
+}
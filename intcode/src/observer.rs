@@ -0,0 +1,186 @@
+use crate::symbols::SymbolTable;
+use log::debug;
+use std::io::Write;
+
+/// Callbacks for instrumenting a running program, e.g. counting instructions or tracing memory
+/// accesses. Every callback does nothing by default, so an observer only implements the events it
+/// cares about.
+pub trait Observer {
+    /// Called before the instruction `value` at `pc` is decoded.
+    fn on_fetch(&mut self, _pc: usize, _value: i64) {}
+    /// Called when an instruction reads a parameter from memory.
+    fn on_read(&mut self, _addr: usize, _value: i64) {}
+    /// Called before an instruction stores `value` at `addr`.
+    fn on_write(&mut self, _addr: usize, _value: i64) {}
+    fn on_input(&mut self, _value: i64) {}
+    fn on_output(&mut self, _value: i64) {}
+    fn on_halt(&mut self) {}
+}
+
+/// Logs every event at debug level, naming addresses from a symbol table if it has one.
+#[derive(Default)]
+pub struct DebugLog {
+    symbols: SymbolTable,
+    /// Where to write the events instead of logging them.
+    out: Option<Box<dyn Write>>,
+}
+
+impl DebugLog {
+    pub fn with_symbols(symbols: SymbolTable) -> DebugLog {
+        DebugLog { symbols, out: None }
+    }
+
+    /// Writes every event to `out`, one per line, instead of logging it, e.g. to trace a program
+    /// on stderr.
+    pub fn writing_to(mut self, out: Box<dyn Write>) -> DebugLog {
+        self.out = Some(out);
+        self
+    }
+
+    fn event(&mut self, line: String) {
+        match &mut self.out {
+            Some(out) => writeln!(out, "{}", line).unwrap_or_default(),
+            None => debug!("{}", line),
+        }
+    }
+
+    fn fetch_line(&self, pc: usize, value: i64) -> String {
+        format!("PC({}) :: {}", self.symbols.name(pc), value)
+    }
+
+    fn read_line(&self, addr: usize, value: i64) -> String {
+        format!("READ [{}] -> {}", self.symbols.name(addr), value)
+    }
+
+    fn write_line(&self, addr: usize, value: i64) -> String {
+        format!("WRITE [{}] <- {}", self.symbols.name(addr), value)
+    }
+}
+
+impl Observer for DebugLog {
+    fn on_fetch(&mut self, pc: usize, value: i64) {
+        let line = self.fetch_line(pc, value);
+        self.event(line);
+    }
+
+    fn on_read(&mut self, addr: usize, value: i64) {
+        let line = self.read_line(addr, value);
+        self.event(line);
+    }
+
+    fn on_write(&mut self, addr: usize, value: i64) {
+        let line = self.write_line(addr, value);
+        self.event(line);
+    }
+
+    fn on_input(&mut self, value: i64) {
+        self.event(format!("INPUT {}", value));
+    }
+
+    fn on_output(&mut self, value: i64) {
+        self.event(format!("OUTPUT {}", value));
+    }
+
+    fn on_halt(&mut self) {
+        self.event("HALT".to_string());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::observer::{DebugLog, Observer};
+    use crate::symbols::SymbolTable;
+    use crate::IntcodeProgram;
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    #[derive(Default)]
+    struct Counter {
+        fetches: usize,
+        reads: usize,
+        writes: Vec<(usize, i64)>,
+        inputs: Vec<i64>,
+        outputs: Vec<i64>,
+        halts: usize,
+    }
+
+    impl Observer for Counter {
+        fn on_fetch(&mut self, _pc: usize, _value: i64) {
+            self.fetches += 1;
+        }
+
+        fn on_read(&mut self, _addr: usize, _value: i64) {
+            self.reads += 1;
+        }
+
+        fn on_write(&mut self, addr: usize, value: i64) {
+            self.writes.push((addr, value));
+        }
+
+        fn on_input(&mut self, value: i64) {
+            self.inputs.push(value);
+        }
+
+        fn on_output(&mut self, value: i64) {
+            self.outputs.push(value);
+        }
+
+        fn on_halt(&mut self) {
+            self.halts += 1;
+        }
+    }
+
+    #[test]
+    fn test_observer_sees_every_event() {
+        let counter = Rc::new(RefCell::new(Counter::default()));
+        let mut program = IntcodeProgram::init_from("3,0,1001,0,5,0,4,0,99");
+        program.add_observer(counter.clone());
+        program.buffer_input(7);
+        program.run();
+
+        let counter = counter.borrow();
+        assert_eq!(counter.fetches, 4);
+        // add reads both parameters, output reads one
+        assert_eq!(counter.reads, 3);
+        assert_eq!(counter.writes, vec![(0, 7), (0, 12)]);
+        assert_eq!(counter.inputs, vec![7]);
+        assert_eq!(counter.outputs, vec![12]);
+        assert_eq!(counter.halts, 1);
+    }
+
+    #[test]
+    fn test_no_input_event_while_waiting() {
+        let counter = Rc::new(RefCell::new(Counter::default()));
+        let mut program = IntcodeProgram::init_from("3,0,99");
+        program.add_observer(counter.clone());
+        program.run();
+        assert!(program.is_awaiting_input());
+        assert!(counter.borrow().inputs.is_empty());
+
+        program.buffer_input(1);
+        program.run();
+        assert_eq!(counter.borrow().inputs, vec![1]);
+        assert_eq!(counter.borrow().halts, 1);
+    }
+
+    #[test]
+    fn test_cleared_observers_are_not_called() {
+        let counter = Rc::new(RefCell::new(Counter::default()));
+        let mut program = IntcodeProgram::init_from("104,1,99");
+        program.add_observer(counter.clone());
+        program.clear_observers();
+        program.run();
+        assert_eq!(counter.borrow().fetches, 0);
+        assert_eq!(program.consume_output(), Some(1));
+    }
+
+    #[test]
+    fn test_debug_log_names_addresses() {
+        let symbols = SymbolTable::parse("0..4 add function\n9 total").unwrap();
+        let log = DebugLog::with_symbols(symbols);
+        assert_eq!(log.fetch_line(0, 1001), "PC(add) :: 1001");
+        assert_eq!(log.read_line(2, 5), "READ [add+2] -> 5");
+        assert_eq!(log.write_line(9, 6), "WRITE [total] <- 6");
+        assert_eq!(DebugLog::default().write_line(9, 6), "WRITE [9] <- 6");
+    }
+}
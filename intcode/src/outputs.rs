@@ -0,0 +1,208 @@
+use crate::{IntcodeError, IntcodeProgram};
+
+/// Iterator over a program's outputs, running the program lazily as outputs are needed. See
+/// [`IntcodeProgram::outputs`].
+pub struct Outputs<'a, F> {
+    program: &'a mut IntcodeProgram,
+    next_input: F,
+    driver: Driver,
+}
+
+/// Runs a program one output at a time, for the adapters that turn a program into a sequence of
+/// its outputs, so they agree on when the outputs end and where an error goes.
+#[derive(Default)]
+pub(crate) struct Driver {
+    /// An error to yield once the outputs from before it have been.
+    error: Option<IntcodeError>,
+    done: bool,
+}
+
+pub(crate) enum Step {
+    Output(i64),
+    Error(IntcodeError),
+    /// The program halted, the error was yielded or the inputs ran out.
+    Done,
+    /// The program is waiting for input that hasn't been buffered.
+    NeedsInput,
+}
+
+impl Driver {
+    /// Runs the program until it has an output, needs more input or stops.
+    pub(crate) fn next_step(&mut self, program: &mut IntcodeProgram) -> Step {
+        loop {
+            if let Some(output) = program.consume_output() {
+                return Step::Output(output);
+            }
+            if let Some(e) = self.error.take() {
+                self.done = true;
+                return Step::Error(e);
+            }
+            if self.done || program.is_halted() {
+                return Step::Done;
+            }
+            if program.is_awaiting_input() && program.input_buf.is_empty() {
+                return Step::NeedsInput;
+            }
+
+            if let Err(e) = program.try_run() {
+                self.error = Some(e);
+            }
+        }
+    }
+
+    /// Ends the outputs early, e.g. when there are no more inputs for the program.
+    pub(crate) fn finish(&mut self) {
+        self.done = true;
+    }
+}
+
+impl IntcodeProgram {
+    /// Runs the program as an iterator of its outputs, calling `next_input` whenever it's waiting
+    /// for input. Ends when the program halts, when `next_input` returns `None`, or after yielding
+    /// an error.
+    pub fn outputs<F>(&mut self, next_input: F) -> Outputs<'_, F>
+    where
+        F: FnMut() -> Option<i64>,
+    {
+        Outputs {
+            program: self,
+            next_input,
+            driver: Driver::default(),
+        }
+    }
+}
+
+impl<'a, F> Outputs<'a, F>
+where
+    F: FnMut() -> Option<i64>,
+{
+    /// Groups the outputs into fixed size chunks, for programs that output e.g. (x, y, tile)
+    /// triples. A chunk cut short by the program halting is an error.
+    pub fn chunks<const N: usize>(self) -> Chunks<'a, F, N> {
+        Chunks { outputs: self }
+    }
+}
+
+impl<F> Iterator for Outputs<'_, F>
+where
+    F: FnMut() -> Option<i64>,
+{
+    type Item = Result<i64, IntcodeError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            match self.driver.next_step(self.program) {
+                Step::Output(output) => return Some(Ok(output)),
+                Step::Error(e) => return Some(Err(e)),
+                Step::Done => return None,
+                Step::NeedsInput => match (self.next_input)() {
+                    Some(value) => self.program.buffer_input(value),
+                    None => {
+                        self.driver.finish();
+                        return None;
+                    }
+                },
+            }
+        }
+    }
+}
+
+pub struct Chunks<'a, F, const N: usize> {
+    outputs: Outputs<'a, F>,
+}
+
+impl<F, const N: usize> Iterator for Chunks<'_, F, N>
+where
+    F: FnMut() -> Option<i64>,
+{
+    type Item = Result<[i64; N], IntcodeError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let mut chunk = [0; N];
+        for (i, slot) in chunk.iter_mut().enumerate() {
+            match self.outputs.next() {
+                Some(Ok(output)) => *slot = output,
+                Some(Err(e)) => return Some(Err(e)),
+                None if i == 0 => return None,
+                None => {
+                    return Some(Err(IntcodeError::IncompleteOutput {
+                        pc: self.outputs.program.pc,
+                        outputs: chunk[..i].to_vec(),
+                        chunk_size: N,
+                    }))
+                }
+            }
+        }
+        Some(Ok(chunk))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{IntcodeError, IntcodeProgram};
+
+    #[test]
+    fn test_outputs_until_halted() {
+        let mut program = IntcodeProgram::init_from("104,1,104,2,99");
+        let outputs: Result<Vec<i64>, IntcodeError> = program.outputs(|| None).collect();
+        assert_eq!(outputs.unwrap(), vec![1, 2]);
+        assert!(program.is_halted());
+    }
+
+    #[test]
+    fn test_outputs_asks_for_inputs_lazily() {
+        // doubles each input until it gets a 0
+        let mut program =
+            IntcodeProgram::init_from("3,20,1006,20,14,1002,20,2,21,4,21,1105,1,0,99");
+        let mut inputs = vec![3, 5, 0].into_iter();
+        let mut num_inputs = 0;
+        let mut outputs = program.outputs(|| {
+            num_inputs += 1;
+            inputs.next()
+        });
+
+        assert_eq!(outputs.next().unwrap().unwrap(), 6);
+        assert_eq!(outputs.next().unwrap().unwrap(), 10);
+        assert!(outputs.next().is_none());
+        drop(outputs);
+        assert_eq!(num_inputs, 3);
+        assert!(program.is_halted());
+
+        // out of inputs
+        let mut program = IntcodeProgram::init_from("3,0,4,0,99");
+        assert_eq!(program.outputs(|| None).count(), 0);
+        assert!(program.is_awaiting_input());
+    }
+
+    #[test]
+    fn test_error_after_outputs() {
+        let mut program = IntcodeProgram::init_from("104,7,98");
+        let mut outputs = program.outputs(|| None);
+        assert_eq!(outputs.next().unwrap().unwrap(), 7);
+        assert_eq!(
+            outputs.next().unwrap().unwrap_err().to_string(),
+            "PC 2: Invalid opcode: 98"
+        );
+        assert!(outputs.next().is_none());
+    }
+
+    #[test]
+    fn test_chunks() {
+        let mut program = IntcodeProgram::init_from("104,1,104,2,104,3,104,4,104,5,104,6,99");
+        let chunks: Vec<[i64; 3]> = program
+            .outputs(|| None)
+            .chunks::<3>()
+            .map(|chunk| chunk.unwrap())
+            .collect();
+        assert_eq!(chunks, vec![[1, 2, 3], [4, 5, 6]]);
+
+        let mut program = IntcodeProgram::init_from("104,1,104,2,104,3,99");
+        let mut chunks = program.outputs(|| None).chunks::<2>();
+        assert_eq!(chunks.next().unwrap().unwrap(), [1, 2]);
+        assert_eq!(
+            chunks.next().unwrap().unwrap_err().to_string(),
+            "PC 6: stopped with an incomplete chunk of outputs [3], expected 2 values"
+        );
+        assert!(chunks.next().is_none());
+    }
+}
@@ -0,0 +1,258 @@
+use crate::IntcodeProgram;
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashSet, VecDeque};
+use std::hash::Hash;
+
+/// A program state reached during a search, along with how it got there.
+pub struct Node {
+    /// The program after the last input sequence was run.
+    pub program: IntcodeProgram,
+    /// Input sequences given to the start program to reach this state, in order.
+    pub path: Vec<Vec<i64>>,
+    /// Outputs produced by the last input sequence.
+    pub outputs: Vec<i64>,
+}
+
+impl Node {
+    pub fn depth(&self) -> usize {
+        self.path.len()
+    }
+}
+
+/// Search over the states of an intcode program. At each state, `moves` gives the candidate
+/// input sequences to try; each one is run on a fork of that state's program. A state is a goal if
+/// `is_goal` accepts the outputs produced by the move that reached it.
+///
+/// Without a state key, every move produces a new state, so `moves` should stop returning moves at
+/// some point (e.g. after a maximum depth). With one, states whose key has already been seen are
+/// dropped.
+pub struct Search<'a, K = ()> {
+    moves: MovesFn<'a>,
+    is_goal: GoalFn<'a>,
+    state_key: Option<KeyFn<'a, K>>,
+}
+
+type MovesFn<'a> = Box<dyn Fn(&Node) -> Vec<Vec<i64>> + 'a>;
+type GoalFn<'a> = Box<dyn Fn(&[i64]) -> bool + 'a>;
+type KeyFn<'a, K> = Box<dyn Fn(&Node) -> K + 'a>;
+
+impl<'a> Search<'a> {
+    pub fn new<M, G>(moves: M, is_goal: G) -> Search<'a>
+    where
+        M: Fn(&Node) -> Vec<Vec<i64>> + 'a,
+        G: Fn(&[i64]) -> bool + 'a,
+    {
+        Search {
+            moves: Box::new(moves),
+            is_goal: Box::new(is_goal),
+            state_key: None,
+        }
+    }
+}
+
+impl<'a, K: Hash + Eq> Search<'a, K> {
+    /// Identify states by `key`, so that each state is only expanded the first time it's reached.
+    pub fn dedup_by<K2, F>(self, key: F) -> Search<'a, K2>
+    where
+        K2: Hash + Eq,
+        F: Fn(&Node) -> K2 + 'a,
+    {
+        Search {
+            moves: self.moves,
+            is_goal: self.is_goal,
+            state_key: Some(Box::new(key)),
+        }
+    }
+
+    /// Breadth-first: returns a goal reached with the fewest moves.
+    pub fn bfs(&self, start: &IntcodeProgram) -> Option<Node> {
+        let mut seen = HashSet::new();
+        let root = self.root(start, &mut seen);
+        if (self.is_goal)(&root.outputs) {
+            return Some(root);
+        }
+
+        let mut queue = VecDeque::new();
+        queue.push_back(root);
+
+        while let Some(node) = queue.pop_front() {
+            for child in self.expand(&node, &mut seen) {
+                if (self.is_goal)(&child.outputs) {
+                    return Some(child);
+                }
+                queue.push_back(child);
+            }
+        }
+        None
+    }
+
+    /// Depth-first: returns the first goal found, trying each state's moves in order.
+    pub fn dfs(&self, start: &IntcodeProgram) -> Option<Node> {
+        let mut seen = HashSet::new();
+        let mut stack = vec![self.root(start, &mut seen)];
+
+        while let Some(node) = stack.pop() {
+            if (self.is_goal)(&node.outputs) {
+                return Some(node);
+            }
+
+            // push in reverse so that the first move is explored first
+            let mut children = self.expand(&node, &mut seen);
+            children.reverse();
+            stack.extend(children);
+        }
+        None
+    }
+
+    /// Best-first: always expands the state with the lowest `priority` next (ties go to the state
+    /// reached first), and returns the first goal expanded.
+    pub fn best_first<P, F>(&self, start: &IntcodeProgram, priority: F) -> Option<Node>
+    where
+        P: Ord,
+        F: Fn(&Node) -> P,
+    {
+        let mut seen = HashSet::new();
+        let mut nodes = Vec::new();
+        let mut heap = BinaryHeap::new();
+
+        let root = self.root(start, &mut seen);
+        heap.push(Reverse((priority(&root), 0)));
+        nodes.push(Some(root));
+
+        while let Some(Reverse((_, node_i))) = heap.pop() {
+            let node = nodes[node_i].take().unwrap();
+            if (self.is_goal)(&node.outputs) {
+                return Some(node);
+            }
+
+            for child in self.expand(&node, &mut seen) {
+                heap.push(Reverse((priority(&child), nodes.len())));
+                nodes.push(Some(child));
+            }
+        }
+        None
+    }
+
+    fn root(&self, start: &IntcodeProgram, seen: &mut HashSet<K>) -> Node {
+        let root = Node {
+            program: start.fork(),
+            path: Vec::new(),
+            outputs: Vec::new(),
+        };
+
+        if let Some(key) = &self.state_key {
+            seen.insert(key(&root));
+        }
+        root
+    }
+
+    fn expand(&self, node: &Node, seen: &mut HashSet<K>) -> Vec<Node> {
+        let mut children = Vec::new();
+        for inputs in (self.moves)(node) {
+            let mut program = node.program.fork();
+            inputs.iter().for_each(|input| program.buffer_input(*input));
+            program.run();
+
+            let mut outputs = Vec::new();
+            while let Some(output) = program.consume_output() {
+                outputs.push(output);
+            }
+
+            let mut path = node.path.clone();
+            path.push(inputs);
+
+            let child = Node {
+                program,
+                path,
+                outputs,
+            };
+            if let Some(key) = &self.state_key {
+                if !seen.insert(key(&child)) {
+                    continue;
+                }
+            }
+            children.push(child);
+        }
+        children
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::search::{Node, Search};
+    use crate::IntcodeProgram;
+    use std::cell::RefCell;
+
+    // Reads a value into m[100] and adds it to a running total in m[101], then outputs the total.
+    const ADDER: &str = "3,100,1,100,101,101,4,101,1105,1,0";
+
+    fn add_1_or_3(node: &Node) -> Vec<Vec<i64>> {
+        if node.depth() < 6 {
+            vec![vec![1], vec![3]]
+        } else {
+            Vec::new()
+        }
+    }
+
+    fn total(node: &Node) -> i64 {
+        node.program.mem_value(101)
+    }
+
+    #[test]
+    fn test_bfs_finds_fewest_moves() {
+        let search = Search::new(add_1_or_3, |outputs| outputs == [7]);
+        let node = search.bfs(&IntcodeProgram::init_from(ADDER)).unwrap();
+        assert_eq!(node.path, vec![vec![1], vec![3], vec![3]]);
+        assert_eq!(node.outputs, vec![7]);
+    }
+
+    #[test]
+    fn test_dfs_explores_first_moves_first() {
+        let search = Search::new(add_1_or_3, |outputs| outputs == [7]);
+        let node = search.dfs(&IntcodeProgram::init_from(ADDER)).unwrap();
+        assert_eq!(node.path, vec![vec![1], vec![1], vec![1], vec![1], vec![3]]);
+    }
+
+    #[test]
+    fn test_best_first_by_priority() {
+        // prefer states closest to the goal, which finds the goal taking the big steps first
+        let search = Search::new(add_1_or_3, |outputs| outputs == [7]);
+        let node = search
+            .best_first(&IntcodeProgram::init_from(ADDER), |node| {
+                (7 - total(node)).abs()
+            })
+            .unwrap();
+        assert_eq!(node.path, vec![vec![3], vec![3], vec![1]]);
+    }
+
+    #[test]
+    fn test_dedup_by_state_key() {
+        let expanded = RefCell::new(Vec::new());
+        let moves = |node: &Node| {
+            expanded.borrow_mut().push(total(node));
+            add_1_or_3(node)
+        };
+
+        let search = Search::new(moves, |outputs| outputs == [100]).dedup_by(total);
+        assert!(search.bfs(&IntcodeProgram::init_from(ADDER)).is_none());
+
+        // each total is only expanded once, however many paths reach it
+        let mut expanded = expanded.borrow().clone();
+        let num_expanded = expanded.len();
+        expanded.sort();
+        expanded.dedup();
+        assert_eq!(expanded.len(), num_expanded);
+        assert_eq!(expanded[..5], [0, 1, 2, 3, 4]);
+    }
+
+    #[test]
+    fn test_search_leaves_start_program_untouched() {
+        let mut program = IntcodeProgram::init_from(ADDER);
+        let search = Search::new(add_1_or_3, |outputs| outputs == [4]);
+        assert!(search.bfs(&program).is_some());
+
+        program.buffer_input(2);
+        program.run();
+        assert_eq!(program.consume_output(), Some(2));
+    }
+}
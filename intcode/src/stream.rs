@@ -0,0 +1,237 @@
+//! An async adapter for running a program inside a task, enabled by the `async` feature.
+//!
+//! [`into_async`] splits a program into a [`Stream`] of its outputs and a [`Sink`] for its inputs.
+//! Polling the stream runs the program; when the program needs input that hasn't been sent yet,
+//! the stream returns `Pending` and its task is woken once the sink gets more input. Both halves
+//! share the program, so they're meant for tasks on the same thread.
+
+use crate::outputs::{Driver, Step};
+use crate::{IntcodeError, IntcodeProgram};
+use futures_core::Stream;
+use futures_sink::Sink;
+use std::cell::RefCell;
+use std::convert::Infallible;
+use std::future::poll_fn;
+use std::pin::Pin;
+use std::rc::Rc;
+use std::task::{Context, Poll, Waker};
+
+struct Shared {
+    program: IntcodeProgram,
+    /// The output stream's task, while it's waiting for input.
+    waiting: Option<Waker>,
+    driver: Driver,
+}
+
+/// The program's outputs. Ends when the program halts, or after yielding an error.
+pub struct AsyncOutputs {
+    shared: Rc<RefCell<Shared>>,
+}
+
+/// Sends inputs to the program. Never applies backpressure, since inputs are buffered.
+#[derive(Clone)]
+pub struct AsyncInputs {
+    shared: Rc<RefCell<Shared>>,
+}
+
+pub fn into_async(program: IntcodeProgram) -> (AsyncOutputs, AsyncInputs) {
+    let shared = Rc::new(RefCell::new(Shared {
+        program,
+        waiting: None,
+        driver: Driver::default(),
+    }));
+    (
+        AsyncOutputs {
+            shared: shared.clone(),
+        },
+        AsyncInputs { shared },
+    )
+}
+
+impl AsyncOutputs {
+    /// The next output, running the program as far as needed to produce it.
+    pub async fn next_output(&mut self) -> Option<Result<i64, IntcodeError>> {
+        poll_fn(|cx| Pin::new(&mut *self).poll_next(cx)).await
+    }
+
+    /// Collects the remaining outputs until the program halts.
+    pub async fn collect_outputs(&mut self) -> Result<Vec<i64>, IntcodeError> {
+        let mut outputs = Vec::new();
+        while let Some(output) = self.next_output().await {
+            outputs.push(output?);
+        }
+        Ok(outputs)
+    }
+}
+
+impl Stream for AsyncOutputs {
+    type Item = Result<i64, IntcodeError>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let mut shared = self.shared.borrow_mut();
+        let shared = &mut *shared;
+        match shared.driver.next_step(&mut shared.program) {
+            Step::Output(output) => Poll::Ready(Some(Ok(output))),
+            Step::Error(e) => Poll::Ready(Some(Err(e))),
+            Step::Done => Poll::Ready(None),
+            Step::NeedsInput => {
+                shared.waiting = Some(cx.waker().clone());
+                Poll::Pending
+            }
+        }
+    }
+}
+
+impl AsyncInputs {
+    pub async fn send_input(&mut self, value: i64) {
+        self.buffer(value);
+    }
+
+    fn buffer(&self, value: i64) {
+        let mut shared = self.shared.borrow_mut();
+        shared.program.buffer_input(value);
+        if let Some(waker) = shared.waiting.take() {
+            waker.wake();
+        }
+    }
+}
+
+impl Sink<i64> for AsyncInputs {
+    type Error = Infallible;
+
+    fn poll_ready(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<Result<(), Infallible>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn start_send(self: Pin<&mut Self>, value: i64) -> Result<(), Infallible> {
+        self.buffer(value);
+        Ok(())
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<Result<(), Infallible>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn poll_close(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<Result<(), Infallible>> {
+        Poll::Ready(Ok(()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::stream::into_async;
+    use crate::IntcodeProgram;
+    use std::cell::RefCell;
+    use std::future::Future;
+    use std::pin::Pin;
+    use std::rc::Rc;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+    use std::task::{Context, Poll, Wake, Waker};
+
+    struct CountingWaker(AtomicUsize);
+
+    impl Wake for CountingWaker {
+        fn wake(self: Arc<Self>) {
+            self.0.fetch_add(1, Ordering::SeqCst);
+        }
+    }
+
+    type Task = Pin<Box<dyn Future<Output = ()>>>;
+
+    /// Polls every task in turn until they've all finished, returning how many times they were
+    /// woken. Panics if every task is stuck.
+    fn run_tasks(mut tasks: Vec<Task>) -> usize {
+        let wakes = Arc::new(CountingWaker(AtomicUsize::new(0)));
+        let waker = Waker::from(wakes.clone());
+        let mut cx = Context::from_waker(&waker);
+
+        while !tasks.is_empty() {
+            let before = wakes.0.load(Ordering::SeqCst);
+            let num_tasks = tasks.len();
+            tasks.retain_mut(|task| task.as_mut().poll(&mut cx) == Poll::Pending);
+            let woken = wakes.0.load(Ordering::SeqCst) > before;
+            assert!(woken || tasks.len() < num_tasks, "Every task is waiting");
+        }
+        wakes.0.load(Ordering::SeqCst)
+    }
+
+    #[test]
+    fn test_outputs_until_halted() {
+        let program = IntcodeProgram::init_from("104,1,104,2,99");
+        let (mut outputs, _) = into_async(program);
+        let collected = Rc::new(RefCell::new(Vec::new()));
+
+        let result = collected.clone();
+        run_tasks(vec![Box::pin(async move {
+            *result.borrow_mut() = outputs.collect_outputs().await.unwrap();
+        })]);
+        assert_eq!(*collected.borrow(), vec![1, 2]);
+    }
+
+    #[test]
+    fn test_waiting_for_input_suspends_the_task() {
+        // doubles each input until it gets a 0
+        let program = IntcodeProgram::init_from("3,20,1006,20,14,1002,20,2,21,4,21,1105,1,0,99");
+        let (mut outputs, mut inputs) = into_async(program);
+        let collected = Rc::new(RefCell::new(Vec::new()));
+
+        let result = collected.clone();
+        let consumer: Task = Box::pin(async move {
+            while let Some(output) = outputs.next_output().await {
+                result.borrow_mut().push(output.unwrap());
+            }
+        });
+        let sent = Rc::new(RefCell::new(Vec::new()));
+        let sent_by_producer = sent.clone();
+        let producer: Task = Box::pin(async move {
+            for value in [3, 5, 0].iter() {
+                inputs.send_input(*value).await;
+                sent_by_producer.borrow_mut().push(*value);
+                // give the consumer a turn
+                Yield(false).await;
+            }
+        });
+
+        let wakes = run_tasks(vec![consumer, producer]);
+        assert_eq!(*collected.borrow(), vec![6, 10]);
+        assert_eq!(*sent.borrow(), vec![3, 5, 0]);
+        // the consumer was suspended waiting for input before being woken by the producer
+        assert!(wakes >= 3);
+    }
+
+    #[test]
+    fn test_error_ends_the_stream() {
+        let program = IntcodeProgram::init_from("104,7,98");
+        let (mut outputs, _) = into_async(program);
+        let collected = Rc::new(RefCell::new(Vec::new()));
+
+        let result = collected.clone();
+        run_tasks(vec![Box::pin(async move {
+            while let Some(output) = outputs.next_output().await {
+                result.borrow_mut().push(output.map_err(|e| e.to_string()));
+            }
+        })]);
+        let collected = collected.borrow();
+        assert_eq!(collected[0], Ok(7));
+        assert!(collected[1].is_err());
+        assert_eq!(collected.len(), 2);
+    }
+
+    /// Returns `Pending` once, waking the task immediately.
+    struct Yield(bool);
+
+    impl Future for Yield {
+        type Output = ();
+
+        fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+            if self.0 {
+                Poll::Ready(())
+            } else {
+                self.0 = true;
+                cx.waker().wake_by_ref();
+                Poll::Pending
+            }
+        }
+    }
+}
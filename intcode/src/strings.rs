@@ -0,0 +1,257 @@
+//! Finding the text in ASCII programs, e.g. to read their messages without playing through them.
+//!
+//! `scan_strings` looks for text stored as is. Programs that only decode their text as they print
+//! it are run with a `TaintTracker` instead, and `output_strings` traces each string they print
+//! back to where it's stored.
+
+use crate::limits::{Limit, Limits};
+use crate::taint::{TaintTracker, TaintedOutput};
+use crate::{IntcodeError, IntcodeProgram};
+use std::cell::RefCell;
+use std::collections::BTreeSet;
+use std::ops::Range;
+use std::rc::Rc;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FoundString {
+    /// The addresses of the string's characters.
+    pub range: Range<usize>,
+    pub text: String,
+}
+
+fn is_text(value: i64) -> bool {
+    value == '\n' as i64 || (32..127).contains(&value)
+}
+
+fn as_text(values: &[i64]) -> String {
+    values.iter().map(|value| *value as u8 as char).collect()
+}
+
+/// The length of the string starting at `addr`, if the word there is the length of the printable
+/// string after it.
+fn prefixed_len(memory: &[i64], addr: usize, min_len: usize) -> Option<usize> {
+    let len = memory[addr];
+    if len < min_len.max(1) as i64 || addr + 1 + len as usize > memory.len() {
+        return None;
+    }
+    let len = len as usize;
+    if memory[addr + 1..addr + 1 + len]
+        .iter()
+        .all(|value| is_text(*value))
+    {
+        Some(len)
+    } else {
+        None
+    }
+}
+
+/// Finds runs of at least `min_len` printable ASCII characters in `memory`. Programs usually store
+/// a string's length before it (the day 17 and 21 programs do), so a run is split wherever a word
+/// is followed by that many printable characters. That can split plain text that happens to
+/// contain such a word, e.g. a space followed by 32 more characters.
+pub fn scan_strings(memory: &[i64], min_len: usize) -> Vec<FoundString> {
+    let mut strings = Vec::new();
+    let add_run = |range: Range<usize>, strings: &mut Vec<FoundString>| {
+        if range.len() >= min_len.max(1) {
+            strings.push(FoundString {
+                text: as_text(&memory[range.clone()]),
+                range,
+            });
+        }
+    };
+
+    let mut run_start = 0;
+    let mut addr = 0;
+    while addr < memory.len() {
+        if let Some(len) = prefixed_len(memory, addr, min_len) {
+            add_run(run_start..addr, &mut strings);
+            add_run(addr + 1..addr + 1 + len, &mut strings);
+            addr += 1 + len;
+            run_start = addr;
+        } else if is_text(memory[addr]) {
+            addr += 1;
+        } else {
+            add_run(run_start..addr, &mut strings);
+            addr += 1;
+            run_start = addr;
+        }
+    }
+    add_run(run_start..addr, &mut strings);
+    strings
+}
+
+/// The address that the `i`th output was printed from: the only one of its sources that neither
+/// the output before nor the one after it came from too. The rest are the code and variables that
+/// printed it.
+fn origin(outputs: &[TaintedOutput], i: usize) -> Option<usize> {
+    if !is_text(outputs[i].value) {
+        return None;
+    }
+    let mut own = outputs[i].addresses();
+    for neighbour in [i.checked_sub(1), Some(i + 1)].iter().flatten() {
+        if let Some(neighbour) = outputs.get(*neighbour) {
+            own = own.difference(&neighbour.addresses()).cloned().collect();
+        }
+    }
+    match own.len() {
+        1 => own.into_iter().next(),
+        _ => None,
+    }
+}
+
+/// Finds the strings of at least `min_len` characters in what a program printed, from the outputs
+/// a `TaintTracker` saw. Each is traced back to the range of addresses its characters were decoded
+/// from, so this finds strings that are obfuscated in memory, but only the ones the program has
+/// printed. Strings printed more than once are only returned once, in order of address.
+pub fn output_strings(outputs: &[TaintedOutput], min_len: usize) -> Vec<FoundString> {
+    let origins: Vec<Option<usize>> = (0..outputs.len()).map(|i| origin(outputs, i)).collect();
+
+    let mut strings = BTreeSet::new();
+    let mut start = 0;
+    for i in 1..=outputs.len() {
+        let continues = match (origins[i - 1], origins.get(i)) {
+            (Some(prev), Some(Some(addr))) => *addr == prev + 1,
+            _ => false,
+        };
+        if continues {
+            continue;
+        }
+        if let Some(first) = origins[start] {
+            if i - start >= min_len.max(1) {
+                let values: Vec<i64> = outputs[start..i].iter().map(|o| o.value).collect();
+                strings.insert((first, i - start, as_text(&values)));
+            }
+        }
+        start = i;
+    }
+
+    strings
+        .into_iter()
+        .map(|(first, len, text)| FoundString {
+            range: first..first + len,
+            text,
+        })
+        .collect()
+}
+
+/// How many instructions `printed_strings` runs when its limits don't say.
+pub const DEFAULT_MAX_INSTRUCTIONS: u64 = 10_000_000;
+
+/// Runs a program on `inputs` until it halts or needs more input, and returns the strings it
+/// printed, as found by `output_strings`. The program runs with `limits`, and at most
+/// `DEFAULT_MAX_INSTRUCTIONS` if they don't limit instructions, so that one that never stops
+/// can't hang; the strings it printed until it went over are still returned.
+pub fn printed_strings(
+    memory: &[i64],
+    inputs: Vec<i64>,
+    min_len: usize,
+    mut limits: Limits,
+) -> Result<Vec<FoundString>, IntcodeError> {
+    let tracker = Rc::new(RefCell::new(TaintTracker::new()));
+    let mut program = IntcodeProgram::try_init(memory, inputs)?;
+    limits
+        .max_instructions
+        .get_or_insert(DEFAULT_MAX_INSTRUCTIONS);
+    program.set_limits(limits);
+    program.add_observer(tracker.clone());
+    match program.try_run() {
+        Ok(())
+        | Err(IntcodeError::LimitExceeded {
+            limit: Limit::Instructions(_),
+            ..
+        }) => {}
+        Err(e) => return Err(e),
+    }
+
+    let tracker = tracker.borrow();
+    Ok(output_strings(tracker.outputs(), min_len))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::limits::Limits;
+    use crate::parse_intcode_input;
+    use crate::strings::{printed_strings, scan_strings, FoundString};
+
+    fn found(start: usize, text: &str) -> FoundString {
+        FoundString {
+            range: start..start + text.len(),
+            text: text.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_scan_plain_and_prefixed_strings() {
+        let mut memory = vec![99, 0];
+        memory.extend("hello".chars().map(|c| c as i64));
+        memory.extend(vec![
+            -1, 3, 'a' as i64, 'b' as i64, 'c' as i64, 2, 'x' as i64,
+        ]);
+        memory.extend(vec![4, 'y' as i64, 'e' as i64, 's' as i64, '\n' as i64]);
+        assert_eq!(
+            scan_strings(&memory, 3),
+            vec![found(2, "hello"), found(9, "abc"), found(15, "yes\n")]
+        );
+        assert_eq!(scan_strings(&memory, 6), vec![]);
+    }
+
+    #[test]
+    fn test_scan_day21() {
+        let memory = parse_intcode_input(include_str!("../../day21/day21.txt"));
+        let strings = scan_strings(&memory, 8);
+        assert!(strings.contains(&found(967, "Input instructions:\n")));
+        assert!(strings.contains(&found(1002, "\nRunning...\n\n")));
+
+        // the program prints them from where they're stored
+        let printed = printed_strings(&memory, Vec::new(), 8, Limits::default()).unwrap();
+        assert_eq!(printed, vec![found(967, "Input instructions:\n")]);
+    }
+
+    #[test]
+    fn test_find_obfuscated_strings_at_runtime() {
+        let memory = parse_intcode_input(include_str!("../../day25/day25.txt"));
+        assert!(scan_strings(&memory, 4)
+            .iter()
+            .all(|string| !string.text.contains("Hull")));
+
+        let printed = printed_strings(&memory, Vec::new(), 4, Limits::default()).unwrap();
+        assert!(printed.contains(&found(3132, "Hull Breach")));
+        assert!(printed.contains(&found(47, "\n\nDoors here lead:\n")));
+        assert!(printed.contains(&found(167, "\nCommand?\n")));
+    }
+
+    #[test]
+    fn test_strings_printed_from_immediates() {
+        // prints "Hi!" with one instruction per character, so the characters aren't stored together
+        let memory = parse_intcode_input("104,72,104,105,104,33,104,500,99");
+        assert_eq!(
+            printed_strings(&memory, Vec::new(), 2, Limits::default()).unwrap(),
+            vec![]
+        );
+        assert_eq!(
+            printed_strings(&memory, Vec::new(), 1, Limits::default()).unwrap(),
+            vec![found(1, "H"), found(3, "i"), found(5, "!")]
+        );
+    }
+
+    #[test]
+    fn test_printed_strings_of_endless_program() {
+        // prints "Hi" forever
+        let memory = parse_intcode_input("104,72,104,105,1105,1,0");
+        let limits = Limits {
+            max_instructions: Some(100),
+            ..Default::default()
+        };
+        assert_eq!(
+            printed_strings(&memory, Vec::new(), 1, limits).unwrap(),
+            vec![found(1, "H"), found(3, "i")]
+        );
+
+        // other limits still fail
+        let limits = Limits {
+            max_outputs: Some(10),
+            ..Default::default()
+        };
+        assert!(printed_strings(&memory, Vec::new(), 1, limits).is_err());
+    }
+}
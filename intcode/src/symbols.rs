@@ -0,0 +1,272 @@
+//! Names for addresses in a program, e.g. the variables and functions found while reverse
+//! engineering it, so that tools can show `ball_y` instead of `389`.
+//!
+//! The text format has one symbol per line: an address or a half-open range of addresses, a name
+//! and optionally what kind of thing is there (`variable`, the default, `function` or `string`).
+//! Blank lines and anything after a `#` are ignored.
+//!
+//! ```text
+//! 0         quarters
+//! 365..369  check_ball_y  function
+//! 389       ball_y        variable
+//! ```
+
+use crate::image::Metadata;
+use std::fmt::{Display, Formatter};
+use std::ops::Range;
+use std::str::FromStr;
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum SymbolKind {
+    Variable,
+    Function,
+    /// A table of strings, e.g. messages the program prints.
+    String,
+}
+
+impl SymbolKind {
+    fn name(&self) -> &'static str {
+        match self {
+            SymbolKind::Variable => "variable",
+            SymbolKind::Function => "function",
+            SymbolKind::String => "string",
+        }
+    }
+}
+
+impl FromStr for SymbolKind {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<SymbolKind, ()> {
+        match s {
+            "variable" => Ok(SymbolKind::Variable),
+            "function" => Ok(SymbolKind::Function),
+            "string" => Ok(SymbolKind::String),
+            _ => Err(()),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Symbol {
+    pub range: Range<usize>,
+    pub name: String,
+    pub kind: SymbolKind,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SymbolTable {
+    /// Sorted by start address.
+    symbols: Vec<Symbol>,
+}
+
+impl SymbolTable {
+    pub fn new(mut symbols: Vec<Symbol>) -> SymbolTable {
+        symbols.sort_by_key(|symbol| (symbol.range.start, symbol.range.end));
+        SymbolTable { symbols }
+    }
+
+    /// Reads the symbols saved in an image's metadata, which are all single variables.
+    pub fn from_metadata(metadata: &Metadata) -> SymbolTable {
+        SymbolTable::new(
+            metadata
+                .symbols
+                .iter()
+                .map(|(addr, name)| Symbol {
+                    range: *addr..*addr + 1,
+                    name: name.clone(),
+                    kind: SymbolKind::Variable,
+                })
+                .collect(),
+        )
+    }
+
+    /// The start address and name of every symbol, to save in an image's metadata.
+    pub fn to_metadata_symbols(&self) -> Vec<(usize, String)> {
+        self.symbols
+            .iter()
+            .map(|symbol| (symbol.range.start, symbol.name.clone()))
+            .collect()
+    }
+
+    pub fn symbols(&self) -> &[Symbol] {
+        &self.symbols
+    }
+
+    /// The innermost symbol containing `addr`, if any.
+    pub fn lookup(&self, addr: usize) -> Option<&Symbol> {
+        self.symbols
+            .iter()
+            .filter(|symbol| symbol.range.contains(&addr))
+            .min_by_key(|symbol| symbol.range.len())
+    }
+
+    /// The address of the symbol called `name`.
+    pub fn address(&self, name: &str) -> Option<usize> {
+        self.symbols
+            .iter()
+            .find(|symbol| symbol.name == name)
+            .map(|symbol| symbol.range.start)
+    }
+
+    /// How to show `addr`: the name of the symbol it's in, plus an offset if it's not the start,
+    /// or just the number.
+    pub fn name(&self, addr: usize) -> String {
+        match self.lookup(addr) {
+            Some(symbol) if addr == symbol.range.start => symbol.name.clone(),
+            Some(symbol) => format!("{}+{}", symbol.name, addr - symbol.range.start),
+            None => addr.to_string(),
+        }
+    }
+
+    pub fn parse(text: &str) -> Result<SymbolTable, SymbolParseError> {
+        let mut symbols = Vec::new();
+        for (line_i, line) in text.lines().enumerate() {
+            let line = match line.find('#') {
+                Some(comment_start) => &line[..comment_start],
+                None => line,
+            };
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            if fields.is_empty() {
+                continue;
+            }
+
+            let bad_line = || SymbolParseError {
+                line: line_i + 1,
+                text: line.trim().to_string(),
+            };
+            let range = match fields[0].split_once("..") {
+                Some((start, end)) => {
+                    let start = start.parse::<usize>().map_err(|_| bad_line())?;
+                    let end = end.parse::<usize>().map_err(|_| bad_line())?;
+                    if end <= start {
+                        return Err(bad_line());
+                    }
+                    start..end
+                }
+                None => {
+                    let addr = fields[0].parse::<usize>().map_err(|_| bad_line())?;
+                    addr..addr.checked_add(1).ok_or_else(bad_line)?
+                }
+            };
+            let kind = match fields.get(2) {
+                Some(kind) => kind.parse().map_err(|_| bad_line())?,
+                None => SymbolKind::Variable,
+            };
+            if fields.len() < 2 || fields.len() > 3 {
+                return Err(bad_line());
+            }
+            symbols.push(Symbol {
+                range,
+                name: fields[1].to_string(),
+                kind,
+            });
+        }
+        Ok(SymbolTable::new(symbols))
+    }
+}
+
+impl Display for SymbolTable {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), std::fmt::Error> {
+        for symbol in self.symbols.iter() {
+            let range = if symbol.range.len() == 1 {
+                symbol.range.start.to_string()
+            } else {
+                format!("{}..{}", symbol.range.start, symbol.range.end)
+            };
+            writeln!(f, "{} {} {}", range, symbol.name, symbol.kind.name())?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub struct SymbolParseError {
+    pub line: usize,
+    pub text: String,
+}
+
+impl Display for SymbolParseError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), std::fmt::Error> {
+        write!(
+            f,
+            "line {}: expected an address or range, a name and optionally a kind, got '{}'",
+            self.line, self.text
+        )
+    }
+}
+
+impl std::error::Error for SymbolParseError {}
+
+#[cfg(test)]
+mod tests {
+    use crate::image::Metadata;
+    use crate::symbols::{SymbolKind, SymbolParseError, SymbolTable};
+
+    const DAY13: &str = "\
+        # day13's game\n\
+        0 quarters\n\
+        365..369 check_ball_y function  # ball_y < 23 -> ball_in_play\n\
+        381 ball_in_play\n\
+        389 ball_y variable\n";
+
+    #[test]
+    fn test_parse_and_lookup() {
+        let symbols = SymbolTable::parse(DAY13).unwrap();
+        assert_eq!(symbols.symbols().len(), 4);
+        assert_eq!(symbols.name(389), "ball_y");
+        assert_eq!(symbols.name(367), "check_ball_y+2");
+        assert_eq!(symbols.name(390), "390");
+        assert_eq!(symbols.lookup(365).unwrap().kind, SymbolKind::Function);
+        assert_eq!(symbols.address("quarters"), Some(0));
+        assert_eq!(symbols.address("paddle_x"), None);
+
+        // round trip through the text format
+        assert_eq!(SymbolTable::parse(&symbols.to_string()).unwrap(), symbols);
+    }
+
+    #[test]
+    fn test_innermost_symbol() {
+        let symbols = SymbolTable::parse("100..200 messages string\n150 greeting").unwrap();
+        assert_eq!(symbols.name(150), "greeting");
+        assert_eq!(symbols.name(151), "messages+51");
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        for (text, line) in [
+            ("0 a\nx b", 2),
+            ("5..5 empty", 1),
+            ("5 a thing", 1),
+            ("5", 1),
+            ("5 a b c", 1),
+            ("18446744073709551615 last", 1),
+        ]
+        .iter()
+        {
+            let error = SymbolTable::parse(text).unwrap_err();
+            assert_eq!(error.line, *line, "{}", text);
+        }
+        assert_eq!(
+            SymbolTable::parse("0..x counter").unwrap_err(),
+            SymbolParseError {
+                line: 1,
+                text: "0..x counter".to_string(),
+            }
+        );
+    }
+
+    #[test]
+    fn test_image_metadata() {
+        let symbols = SymbolTable::parse(DAY13).unwrap();
+        let metadata = Metadata {
+            symbols: symbols.to_metadata_symbols(),
+            ..Default::default()
+        };
+        let from_image = SymbolTable::from_metadata(&metadata);
+        assert_eq!(from_image.name(389), "ball_y");
+        assert_eq!(from_image.name(365), "check_ball_y");
+        // ranges and kinds aren't kept in images
+        assert_eq!(from_image.name(366), "366");
+    }
+}
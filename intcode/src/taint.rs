@@ -0,0 +1,180 @@
+//! Taint tracking: which inputs and which values of the original program ended up in each output
+//! and memory cell.
+//!
+//! Every input a program reads is labelled with its index, and every memory cell starts out
+//! labelled with its own address. An instruction's result carries the union of the labels of
+//! everything it read, including immediate operands, which are constants in the program's memory.
+//! Only data flow is tracked: a value that's only compared and jumped on doesn't label whatever the
+//! program does on either side of the jump.
+
+use crate::observer::Observer;
+use std::collections::{BTreeSet, HashMap};
+
+/// Where a value came from.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Source {
+    /// The nth value the program read as input, counting from 0.
+    Input(usize),
+    /// The value at this address when the program was loaded.
+    Memory(usize),
+}
+
+#[derive(Debug, PartialEq)]
+pub struct TaintedOutput {
+    pub value: i64,
+    pub sources: BTreeSet<Source>,
+}
+
+impl TaintedOutput {
+    /// The indices of the inputs that influenced the output.
+    pub fn inputs(&self) -> BTreeSet<usize> {
+        self.sources
+            .iter()
+            .filter_map(|source| match source {
+                Source::Input(i) => Some(*i),
+                Source::Memory(_) => None,
+            })
+            .collect()
+    }
+
+    /// The addresses of the original memory values that influenced the output.
+    pub fn addresses(&self) -> BTreeSet<usize> {
+        self.sources
+            .iter()
+            .filter_map(|source| match source {
+                Source::Memory(addr) => Some(*addr),
+                Source::Input(_) => None,
+            })
+            .collect()
+    }
+}
+
+/// An observer that propagates labels as the program runs. Attach it before running the program.
+#[derive(Default)]
+pub struct TaintTracker {
+    /// The labels of every cell that's been written. Other cells still hold their original value.
+    cells: HashMap<usize, BTreeSet<Source>>,
+    /// The labels of everything read by the current instruction.
+    current: BTreeSet<Source>,
+    num_inputs: usize,
+    outputs: Vec<TaintedOutput>,
+}
+
+impl TaintTracker {
+    pub fn new() -> TaintTracker {
+        Default::default()
+    }
+
+    /// Every output so far, with what influenced it.
+    pub fn outputs(&self) -> &[TaintedOutput] {
+        &self.outputs
+    }
+
+    /// What influenced the value currently at `addr`.
+    pub fn sources_at(&self, addr: usize) -> BTreeSet<Source> {
+        match self.cells.get(&addr) {
+            Some(sources) => sources.clone(),
+            None => [Source::Memory(addr)].iter().cloned().collect(),
+        }
+    }
+
+    /// The addresses of the memory cells whose current value was influenced by `source`.
+    pub fn influenced_by(&self, source: Source) -> Vec<usize> {
+        let mut addrs: Vec<usize> = self
+            .cells
+            .iter()
+            .filter(|(_, sources)| sources.contains(&source))
+            .map(|(addr, _)| *addr)
+            .collect();
+        addrs.sort_unstable();
+        addrs
+    }
+}
+
+impl Observer for TaintTracker {
+    fn on_fetch(&mut self, _pc: usize, _value: i64) {
+        self.current.clear();
+    }
+
+    fn on_read(&mut self, addr: usize, _value: i64) {
+        let sources = self.sources_at(addr);
+        self.current.extend(sources);
+    }
+
+    fn on_write(&mut self, addr: usize, _value: i64) {
+        self.cells.insert(addr, self.current.clone());
+    }
+
+    fn on_input(&mut self, _value: i64) {
+        self.current.insert(Source::Input(self.num_inputs));
+        self.num_inputs += 1;
+    }
+
+    fn on_output(&mut self, value: i64) {
+        self.outputs.push(TaintedOutput {
+            value,
+            sources: self.current.clone(),
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::taint::{Source, TaintTracker};
+    use crate::IntcodeProgram;
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    fn run(intcode: &str, inputs: Vec<i64>) -> Rc<RefCell<TaintTracker>> {
+        let tracker = Rc::new(RefCell::new(TaintTracker::new()));
+        let mut program = IntcodeProgram::init_from(intcode);
+        program.add_observer(tracker.clone());
+        inputs
+            .into_iter()
+            .for_each(|input| program.buffer_input(input));
+        program.run();
+        tracker
+    }
+
+    #[test]
+    fn test_comparison_is_tainted() {
+        // is the input equal to 8?
+        let tracker = run("3,9,8,9,10,9,4,9,99,-1,8", vec![8]);
+        let tracker = tracker.borrow();
+        let output = &tracker.outputs()[0];
+        assert_eq!(output.value, 1);
+        assert_eq!(
+            output.sources,
+            vec![Source::Input(0), Source::Memory(10)]
+                .into_iter()
+                .collect()
+        );
+    }
+
+    #[test]
+    fn test_inputs_are_tracked_separately() {
+        // stores two inputs, outputs the second plus 5
+        let tracker = run("3,20,3,21,1001,21,5,22,4,22,99", vec![1, 2]);
+        let tracker = tracker.borrow();
+        let output = &tracker.outputs()[0];
+        assert_eq!(output.value, 7);
+        assert_eq!(output.inputs(), vec![1].into_iter().collect());
+        // the immediate 5
+        assert_eq!(output.addresses(), vec![6].into_iter().collect());
+
+        assert_eq!(tracker.influenced_by(Source::Input(0)), vec![20]);
+        assert_eq!(tracker.influenced_by(Source::Input(1)), vec![21, 22]);
+        assert_eq!(
+            tracker.sources_at(30),
+            vec![Source::Memory(30)].into_iter().collect()
+        );
+    }
+
+    #[test]
+    fn test_constant_outputs_are_untainted_by_inputs() {
+        let tracker = run("3,0,104,7,99", vec![1]);
+        let tracker = tracker.borrow();
+        assert!(tracker.outputs()[0].inputs().is_empty());
+        assert_eq!(tracker.influenced_by(Source::Input(0)), vec![0]);
+    }
+}
@@ -0,0 +1,511 @@
+//! Recording and replaying a program's I/O.
+//!
+//! A transcript is the sequence of values a program consumed and produced, in the order it did so,
+//! so each input is stored right after the outputs that came before it. Replaying a transcript
+//! feeds the same inputs to a fresh program at the same points, which rebuilds the exact state of
+//! the recorded session, and checks that the outputs still match along the way.
+//!
+//! The text format has one line per run of inputs or outputs, e.g. `out 62,10` then `in 110,10`.
+//! A run can also be given as quoted ASCII text, e.g. `in "north\n"`, with `\n`, `\"` and `\\`
+//! escaped; recorded runs of text that end with a newline are written that way. `out ...` matches
+//! whatever the program outputs until it next waits for input, so a transcript written by hand
+//! can give just the inputs. Blank lines and anything after a `#` outside of quotes are ignored.
+
+use crate::observer::Observer;
+use crate::{IntcodeError, IntcodeProgram};
+use std::fmt::{Display, Formatter};
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Event {
+    Input(i64),
+    Output(i64),
+    /// Any number of outputs, which aren't checked. Never recorded.
+    AnyOutputs,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Transcript {
+    pub events: Vec<Event>,
+}
+
+impl Transcript {
+    /// Recorded inputs, each with the number of outputs that came before it, not counting any
+    /// matched by `Event::AnyOutputs`.
+    pub fn inputs(&self) -> Vec<(usize, i64)> {
+        let mut num_outputs = 0;
+        let mut inputs = Vec::new();
+        for event in self.events.iter() {
+            match event {
+                Event::Input(value) => inputs.push((num_outputs, *value)),
+                Event::Output(_) => num_outputs += 1,
+                Event::AnyOutputs => {}
+            }
+        }
+        inputs
+    }
+
+    pub fn outputs(&self) -> Vec<i64> {
+        self.events
+            .iter()
+            .filter_map(|event| match event {
+                Event::Output(value) => Some(*value),
+                Event::Input(_) | Event::AnyOutputs => None,
+            })
+            .collect()
+    }
+
+    pub fn parse(text: &str) -> Result<Transcript, TranscriptParseError> {
+        let mut events = Vec::new();
+        for (line_i, line) in text.lines().enumerate() {
+            let line = strip_comment(line).trim();
+            if line.is_empty() {
+                continue;
+            }
+
+            let bad_line = || TranscriptParseError {
+                line: line_i + 1,
+                text: line.to_string(),
+            };
+            let (kind, values) = line.split_once(' ').ok_or_else(bad_line)?;
+            let values = values.trim();
+            let event: fn(i64) -> Event = match kind {
+                "in" => Event::Input,
+                "out" if values == "..." => {
+                    events.push(Event::AnyOutputs);
+                    continue;
+                }
+                "out" => Event::Output,
+                _ => return Err(bad_line()),
+            };
+            if values.starts_with('"') {
+                let text = unquote(values).ok_or_else(bad_line)?;
+                events.extend(text.chars().map(|c| event(c as i64)));
+                continue;
+            }
+            for value in values.split(',') {
+                let value = value.trim().parse::<i64>().map_err(|_| bad_line())?;
+                events.push(event(value));
+            }
+        }
+        Ok(Transcript { events })
+    }
+}
+
+impl Display for Transcript {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), std::fmt::Error> {
+        // group runs of inputs or outputs onto one line each
+        let mut events = self.events.iter().peekable();
+        while let Some(first) = events.next() {
+            let (kind, mut line) = match first {
+                Event::Input(value) => ("in", vec![*value]),
+                Event::Output(value) => ("out", vec![*value]),
+                Event::AnyOutputs => {
+                    writeln!(f, "out ...")?;
+                    continue;
+                }
+            };
+            while let Some(next) = events.peek() {
+                match (first, next) {
+                    (Event::Input(_), Event::Input(value))
+                    | (Event::Output(_), Event::Output(value)) => line.push(*value),
+                    _ => break,
+                }
+                events.next();
+            }
+            if is_text_line(&line) {
+                let text: String = line.iter().map(|value| *value as u8 as char).collect();
+                writeln!(f, "{} {}", kind, quote(&text))?;
+            } else {
+                let values: Vec<String> = line.iter().map(|value| value.to_string()).collect();
+                writeln!(f, "{} {}", kind, values.join(","))?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub struct TranscriptParseError {
+    pub line: usize,
+    pub text: String,
+}
+
+impl Display for TranscriptParseError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), std::fmt::Error> {
+        write!(
+            f,
+            "line {}: expected 'in' or 'out' followed by comma separated integers or quoted text, \
+             got '{}'",
+            self.line, self.text
+        )
+    }
+}
+
+impl std::error::Error for TranscriptParseError {}
+
+fn is_text(value: i64) -> bool {
+    value == '\n' as i64 || (32..127).contains(&value)
+}
+
+/// Whether a run of values looks like a line of ASCII text rather than numbers that happen to be
+/// printable, i.e. some text followed by a newline.
+fn is_text_line(values: &[i64]) -> bool {
+    values.len() > 1 && values.last() == Some(&('\n' as i64)) && values.iter().all(|v| is_text(*v))
+}
+
+/// The part of a line before a `#` that isn't in quotes.
+fn strip_comment(line: &str) -> &str {
+    let mut in_quotes = false;
+    let mut escaped = false;
+    for (i, c) in line.char_indices() {
+        match c {
+            _ if escaped => escaped = false,
+            '\\' if in_quotes => escaped = true,
+            '"' => in_quotes = !in_quotes,
+            '#' if !in_quotes => return &line[..i],
+            _ => {}
+        }
+    }
+    line
+}
+
+fn quote(s: &str) -> String {
+    let escaped = s
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n");
+    format!("\"{}\"", escaped)
+}
+
+fn unquote(s: &str) -> Option<String> {
+    let s = s.strip_prefix('"')?.strip_suffix('"')?;
+    let mut unquoted = String::new();
+    let mut chars = s.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => match chars.next()? {
+                'n' => unquoted.push('\n'),
+                c @ '"' | c @ '\\' => unquoted.push(c),
+                _ => return None,
+            },
+            '"' => return None,
+            c => unquoted.push(c),
+        }
+    }
+    Some(unquoted)
+}
+
+/// Observer that records a transcript of everything the program consumes and produces.
+#[derive(Default)]
+pub struct Recorder {
+    transcript: Transcript,
+}
+
+impl Recorder {
+    pub fn transcript(&self) -> &Transcript {
+        &self.transcript
+    }
+}
+
+impl Observer for Recorder {
+    fn on_input(&mut self, value: i64) {
+        self.transcript.events.push(Event::Input(value));
+    }
+
+    fn on_output(&mut self, value: i64) {
+        self.transcript.events.push(Event::Output(value));
+    }
+}
+
+/// What the program did instead of the recorded event.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Actual {
+    Output(i64),
+    AwaitingInput,
+    Halted,
+}
+
+/// The first point where a replayed program stopped matching its transcript.
+#[derive(Debug, PartialEq)]
+pub struct Divergence {
+    /// Index of the event in the transcript.
+    pub event: usize,
+    /// Number of outputs that matched before diverging.
+    pub output_position: usize,
+    /// The recorded event, or `None` if the transcript had already ended.
+    pub expected: Option<Event>,
+    pub actual: Actual,
+}
+
+impl Display for Divergence {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), std::fmt::Error> {
+        write!(
+            f,
+            "diverged at event {} (after {} outputs): expected ",
+            self.event, self.output_position
+        )?;
+        match self.expected {
+            Some(Event::Input(_)) => write!(f, "to wait for input")?,
+            Some(Event::Output(value)) => write!(f, "output {}", value)?,
+            Some(Event::AnyOutputs) => write!(f, "any outputs")?,
+            None => write!(f, "the end of the transcript")?,
+        }
+        match self.actual {
+            Actual::Output(value) => write!(f, ", got output {}", value),
+            Actual::AwaitingInput => write!(f, ", but the program is waiting for input"),
+            Actual::Halted => write!(f, ", but the program halted"),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum ReplayError {
+    Diverged(Divergence),
+    Intcode(IntcodeError),
+}
+
+impl Display for ReplayError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), std::fmt::Error> {
+        match self {
+            ReplayError::Diverged(divergence) => write!(f, "{}", divergence),
+            ReplayError::Intcode(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl std::error::Error for ReplayError {}
+
+/// Replays `transcript` on `program`, which should be in the state the recording started from.
+/// Stops at the first divergence. Otherwise, the program is left in the state the recorded session
+/// ended in, and everything it output during the replay is returned.
+pub fn replay(
+    program: &mut IntcodeProgram,
+    transcript: &Transcript,
+) -> Result<Vec<i64>, ReplayError> {
+    let events = &transcript.events;
+    let mut next = 0;
+    let mut outputs = Vec::new();
+
+    loop {
+        program.try_run().map_err(ReplayError::Intcode)?;
+
+        while let Some(output) = program.consume_output() {
+            match events.get(next) {
+                Some(Event::Output(value)) if *value == output => next += 1,
+                Some(Event::AnyOutputs) => {}
+                _ => {
+                    return Err(diverged(
+                        events,
+                        next,
+                        outputs.len(),
+                        Actual::Output(output),
+                    ))
+                }
+            }
+            outputs.push(output);
+        }
+        // the program has output everything it's going to before it stopped
+        while events.get(next) == Some(&Event::AnyOutputs) {
+            next += 1;
+        }
+
+        if program.is_halted() {
+            return if next == events.len() {
+                Ok(outputs)
+            } else {
+                Err(diverged(events, next, outputs.len(), Actual::Halted))
+            };
+        }
+
+        // waiting for input
+        match events.get(next) {
+            None => return Ok(outputs),
+            Some(Event::Input(value)) => {
+                program.buffer_input(*value);
+                next += 1;
+            }
+            Some(_) => return Err(diverged(events, next, outputs.len(), Actual::AwaitingInput)),
+        }
+    }
+}
+
+fn diverged(events: &[Event], next: usize, num_outputs: usize, actual: Actual) -> ReplayError {
+    ReplayError::Diverged(Divergence {
+        event: next,
+        output_position: num_outputs,
+        expected: events.get(next).cloned(),
+        actual,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::transcript::*;
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    // outputs 1, then doubles each input until it gets a 0
+    const DOUBLER: &str = "104,1,3,20,1006,20,16,1002,20,2,21,4,21,1105,1,2,99";
+
+    fn record(intcode: &str, inputs: &[i64]) -> (IntcodeProgram, Transcript) {
+        let recorder = Rc::new(RefCell::new(Recorder::default()));
+        let mut program = IntcodeProgram::init_from(intcode);
+        program.add_observer(recorder.clone());
+
+        for input in inputs.iter() {
+            program.run();
+            while program.consume_output().is_some() {}
+            program.buffer_input(*input);
+        }
+        program.run();
+        while program.consume_output().is_some() {}
+
+        program.clear_observers();
+        let transcript = recorder.borrow().transcript().clone();
+        (program, transcript)
+    }
+
+    #[test]
+    fn test_record() {
+        let (_, transcript) = record(DOUBLER, &[3, 5]);
+        assert_eq!(
+            transcript.events,
+            vec![
+                Event::Output(1),
+                Event::Input(3),
+                Event::Output(6),
+                Event::Input(5),
+                Event::Output(10),
+            ]
+        );
+        assert_eq!(transcript.inputs(), vec![(1, 3), (2, 5)]);
+        assert_eq!(transcript.outputs(), vec![1, 6, 10]);
+    }
+
+    #[test]
+    fn test_text_round_trip() {
+        let (_, transcript) = record(DOUBLER, &[3, 5]);
+        let text = transcript.to_string();
+        assert_eq!(text, "out 1\nin 3\nout 6\nin 5\nout 10\n");
+        assert_eq!(Transcript::parse(&text), Ok(transcript));
+
+        let grouped = Transcript::parse("# comment\nout 1, 2\n\nin -3,4  # more\n").unwrap();
+        assert_eq!(grouped.outputs(), vec![1, 2]);
+        assert_eq!(grouped.inputs(), vec![(2, -3), (2, 4)]);
+        assert_eq!(grouped.to_string(), "out 1,2\nin -3,4\n");
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let error = Transcript::parse("out 1\nin\n").unwrap_err();
+        assert_eq!(error.line, 2);
+        assert_eq!(
+            Transcript::parse("out 1,x").unwrap_err().to_string(),
+            "line 1: expected 'in' or 'out' followed by comma separated integers or quoted text, \
+             got 'out 1,x'"
+        );
+        assert!(Transcript::parse("inout 1").is_err());
+        assert!(Transcript::parse("in ...").is_err());
+        assert!(Transcript::parse("in \"north").is_err());
+        assert!(Transcript::parse("in \"\\t\"").is_err());
+    }
+
+    #[test]
+    fn test_ascii_lines() {
+        let transcript =
+            Transcript::parse("out \"#?\\n\"  # prompt\nin \"say \\\"hi\\\"\\n\"\n").unwrap();
+        assert_eq!(transcript.outputs(), vec![35, 63, 10]);
+        assert_eq!(
+            transcript.inputs(),
+            "say \"hi\"\n"
+                .chars()
+                .map(|c| (3, c as i64))
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(
+            transcript.to_string(),
+            "out \"#?\\n\"\nin \"say \\\"hi\\\"\\n\"\n"
+        );
+
+        // text only gets quoted when it's a whole line
+        let transcript = Transcript::parse("out 72,105\nin \"\\n\"\nout \"y\\n\"").unwrap();
+        assert_eq!(transcript.to_string(), "out 72,105\nin 10\nout \"y\\n\"\n");
+    }
+
+    #[test]
+    fn test_replay_any_outputs() {
+        let transcript = Transcript::parse("out ...\nin 3\nout ...\nin 0").unwrap();
+        assert_eq!(transcript.to_string(), "out ...\nin 3\nout ...\nin 0\n");
+
+        let mut program = IntcodeProgram::init_from(DOUBLER);
+        assert_eq!(replay(&mut program, &transcript).unwrap(), vec![1, 6]);
+        assert!(program.is_halted());
+
+        // the inputs still have to be taken where they're given
+        let transcript = Transcript::parse("out ...\nin 3\nin 5").unwrap();
+        let mut program = IntcodeProgram::init_from(DOUBLER);
+        match replay(&mut program, &transcript) {
+            Err(ReplayError::Diverged(divergence)) => {
+                assert_eq!(divergence.expected, Some(Event::Input(5)));
+                assert_eq!(divergence.actual, Actual::Output(6));
+            }
+            result => panic!("Expected divergence, got {:?}", result),
+        }
+    }
+
+    #[test]
+    fn test_replay_rebuilds_state() {
+        let (recorded, transcript) = record(DOUBLER, &[3, 0]);
+        assert!(recorded.is_halted());
+
+        let mut program = IntcodeProgram::init_from(DOUBLER);
+        assert_eq!(replay(&mut program, &transcript).unwrap(), vec![1, 6]);
+        assert!(program.is_halted());
+        assert_eq!(program.mem_value(20), 0);
+
+        // a session that ended while waiting for input can be continued
+        let (_, transcript) = record(DOUBLER, &[7]);
+        let mut program = IntcodeProgram::init_from(DOUBLER);
+        replay(&mut program, &transcript).unwrap();
+        assert!(program.is_awaiting_input());
+        program.buffer_input(8);
+        program.run();
+        assert_eq!(program.consume_output(), Some(16));
+    }
+
+    #[test]
+    fn test_replay_reports_divergence() {
+        let (_, transcript) = record(DOUBLER, &[3, 5]);
+
+        // outputs 1, then triples its input
+        let tripler = DOUBLER.replace("1002,20,2", "1002,20,3");
+        let mut program = IntcodeProgram::init_from(&tripler);
+        match replay(&mut program, &transcript) {
+            Err(ReplayError::Diverged(divergence)) => {
+                assert_eq!(
+                    divergence,
+                    Divergence {
+                        event: 2,
+                        output_position: 1,
+                        expected: Some(Event::Output(6)),
+                        actual: Actual::Output(9),
+                    }
+                );
+                assert_eq!(
+                    divergence.to_string(),
+                    "diverged at event 2 (after 1 outputs): expected output 6, got output 9"
+                );
+            }
+            result => panic!("Expected divergence, got {:?}", result),
+        }
+
+        let mut program = IntcodeProgram::init_from("104,1,99");
+        match replay(&mut program, &transcript) {
+            Err(ReplayError::Diverged(divergence)) => {
+                assert_eq!(divergence.expected, Some(Event::Input(3)));
+                assert_eq!(divergence.actual, Actual::Halted);
+            }
+            result => panic!("Expected divergence, got {:?}", result),
+        }
+    }
+}
@@ -0,0 +1,56 @@
+//! Checks the compiled backend against the interpreter. The `backends` crate translates every
+//! day's program, and some that fail in every way an instruction can, to Rust, and each one is run
+//! in lockstep with `IntcodeProgram` on the same input.
+
+use backends::{DAYS, FAULTS};
+use intcode::differential::lockstep;
+use intcode::{parse_intcode_input, IntcodeProgram};
+
+fn ascii(text: &str) -> Vec<i64> {
+    text.bytes().map(|b| b as i64).collect()
+}
+
+/// Runs the day's program compiled and interpreted, after patching memory, with `inputs`.
+fn check_day(day: u8, patches: &[(usize, i64)], inputs: &[i64]) {
+    let (_, intcode, compiled) = DAYS.iter().find(|(d, ..)| *d == day).unwrap();
+    let mut memory = parse_intcode_input(intcode);
+    let mut compiled = compiled();
+    for (addr, value) in patches {
+        memory[*addr] = *value;
+        compiled.set_mem_value(*addr, *value);
+    }
+
+    let mut interpreted = IntcodeProgram::init(&memory, Vec::new());
+    if let Err(divergence) = lockstep(&mut interpreted, &mut compiled, inputs) {
+        panic!("Day {}: {}", day, divergence);
+    }
+}
+
+#[test]
+fn test_days_match_interpreter() {
+    check_day(2, &[(1, 12), (2, 2)], &[]);
+    check_day(5, &[], &[5]);
+    check_day(7, &[], &[1, 0]);
+    check_day(7, &[], &[5, 0, 10]);
+    check_day(9, &[], &[1]);
+    check_day(11, &[], &[0, 1, 1, 0, 1, 1, 0, 0, 1]);
+    check_day(13, &[(0, 2)], &[0, -1, 1, 1, 0]);
+    check_day(15, &[], &[1, 2, 3, 4, 1, 1, 4, 4]);
+    check_day(17, &[(0, 2)], &ascii("A\nR,4,L,2\nL,2\nR,4\nn\n"));
+    check_day(19, &[], &[10, 20]);
+    check_day(21, &[], &ascii("NOT A J\nWALK\n"));
+    check_day(23, &[], &[3, -1, -1]);
+    check_day(25, &[], &ascii("north\nsouth\ninv\n"));
+}
+
+#[test]
+fn test_faults_match_interpreter() {
+    for (name, intcode, compiled) in FAULTS.iter() {
+        let mut interpreted = IntcodeProgram::init_from(intcode);
+        let mut compiled = compiled();
+        if let Err(divergence) = lockstep(&mut interpreted, &mut compiled, &[]) {
+            panic!("{}: {}", name, divergence);
+        }
+        assert!(interpreted.try_run().is_err(), "{} didn't fail", name);
+    }
+}